@@ -0,0 +1,178 @@
+//! Shareable, mutable containers for pinned collections.
+//!
+//! [`PinCell<C>`] is a [`RefCell`] around an [`ItemsPin<role::Items, C>`], and [`PinMutex<C>`] (with the `"std"` feature)
+//! is its [`Sync`] counterpart around a [`Mutex`](`std::sync::Mutex`).
+//! Their guards dereference to [`&mut ItemsPin<role::Items, C>`](`ItemsPin`), so a collection shared e.g. through an
+//! [`Rc`](`alloc::rc::Rc`) or [`Arc`](`alloc::sync::Arc`) can be used with `.push_pinned` and the like
+//! without `unsafe` casts.
+//!
+//! Unlike [`RefCell<C>`] and [`Mutex<C>`](`std::sync::Mutex`), neither type hands out the collection itself by value,
+//! and the guards only give access to it through [`ItemsPin`]. As `ItemsPin`'s [`DerefMut`](`core::ops::DerefMut`)
+//! and [`ItemsMut`](`crate::ItemsMut`) implementations require [`Unpin`] items, a collection of items that aren't
+//! can't be swapped out or replaced in a way that would move its items.
+//! (Exchanging two [`ItemsPin`]s through their guards is fine, as that moves whole pinned collections without moving their items.)
+
+use crate::{role, ItemsPin};
+use core::{
+	cell::{BorrowError, BorrowMutError, Ref, RefCell, RefMut},
+	fmt::{self, Debug, Formatter},
+};
+#[cfg(feature = "std")]
+use std::sync::{LockResult, Mutex, MutexGuard, TryLockResult};
+
+/// A [`RefCell`]-like container of a pinned collection, with dynamically checked borrows through `&self`.
+pub struct PinCell<C> {
+	items_pin: RefCell<ItemsPin<role::Items, C>>,
+}
+
+impl<C> PinCell<C> {
+	#[must_use]
+	pub const fn new(items_pin: ItemsPin<role::Items, C>) -> Self {
+		Self {
+			items_pin: RefCell::new(items_pin),
+		}
+	}
+
+	#[must_use]
+	pub fn into_inner(self) -> ItemsPin<role::Items, C> {
+		self.items_pin.into_inner()
+	}
+
+	/// Immutably borrows the pinned collection.
+	///
+	/// # Panics
+	///
+	/// Iff the collection is currently mutably borrowed.
+	#[must_use]
+	pub fn borrow_pinned(&self) -> Ref<'_, ItemsPin<role::Items, C>> {
+		self.items_pin.borrow()
+	}
+
+	/// Mutably borrows the pinned collection.
+	///
+	/// # Panics
+	///
+	/// Iff the collection is currently borrowed.
+	#[must_use]
+	pub fn borrow_pinned_mut(&self) -> RefMut<'_, ItemsPin<role::Items, C>> {
+		self.items_pin.borrow_mut()
+	}
+
+	/// Immutably borrows the pinned collection.
+	///
+	/// # Errors
+	///
+	/// Iff the collection is currently mutably borrowed.
+	pub fn try_borrow_pinned(&self) -> Result<Ref<'_, ItemsPin<role::Items, C>>, BorrowError> {
+		self.items_pin.try_borrow()
+	}
+
+	/// Mutably borrows the pinned collection.
+	///
+	/// # Errors
+	///
+	/// Iff the collection is currently borrowed.
+	pub fn try_borrow_pinned_mut(
+		&self,
+	) -> Result<RefMut<'_, ItemsPin<role::Items, C>>, BorrowMutError> {
+		self.items_pin.try_borrow_mut()
+	}
+
+	/// Accesses the pinned collection without a runtime check, as `self` is borrowed exclusively.
+	pub fn get_mut(&mut self) -> &mut ItemsPin<role::Items, C> {
+		self.items_pin.get_mut()
+	}
+}
+
+impl<C> From<ItemsPin<role::Items, C>> for PinCell<C> {
+	fn from(items_pin: ItemsPin<role::Items, C>) -> Self {
+		Self::new(items_pin)
+	}
+}
+
+impl<C> Debug for PinCell<C>
+where
+	ItemsPin<role::Items, C>: Debug,
+{
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("PinCell")
+			.field("items_pin", &self.items_pin)
+			.finish()
+	}
+}
+
+/// A [`Mutex`]-backed container of a pinned collection that can be shared between threads.
+///
+/// Like [`Mutex<C>`], it's poisoned if a thread panics while holding its lock.
+#[cfg(feature = "std")]
+pub struct PinMutex<C> {
+	items_pin: Mutex<ItemsPin<role::Items, C>>,
+}
+
+#[cfg(feature = "std")]
+impl<C> PinMutex<C> {
+	#[must_use]
+	pub const fn new(items_pin: ItemsPin<role::Items, C>) -> Self {
+		Self {
+			items_pin: Mutex::new(items_pin),
+		}
+	}
+
+	/// # Errors
+	///
+	/// Iff the mutex is poisoned. The collection is still available through the [`PoisonError`](`std::sync::PoisonError`).
+	pub fn into_inner(self) -> LockResult<ItemsPin<role::Items, C>> {
+		self.items_pin.into_inner()
+	}
+
+	/// Blocks until the pinned collection can be locked.
+	///
+	/// # Errors
+	///
+	/// Iff the mutex is poisoned. The guard is still available through the [`PoisonError`](`std::sync::PoisonError`).
+	pub fn lock_pinned(&self) -> LockResult<MutexGuard<'_, ItemsPin<role::Items, C>>> {
+		self.items_pin.lock()
+	}
+
+	/// Locks the pinned collection if that's possible without blocking.
+	///
+	/// # Errors
+	///
+	/// Iff the mutex is locked elsewhere or poisoned.
+	pub fn try_lock_pinned(&self) -> TryLockResult<MutexGuard<'_, ItemsPin<role::Items, C>>> {
+		self.items_pin.try_lock()
+	}
+
+	/// Accesses the pinned collection without locking, as `self` is borrowed exclusively.
+	///
+	/// # Errors
+	///
+	/// Iff the mutex is poisoned. The collection is still available through the [`PoisonError`](`std::sync::PoisonError`).
+	pub fn get_mut(&mut self) -> LockResult<&mut ItemsPin<role::Items, C>> {
+		self.items_pin.get_mut()
+	}
+
+	#[must_use]
+	pub fn is_poisoned(&self) -> bool {
+		self.items_pin.is_poisoned()
+	}
+}
+
+#[cfg(feature = "std")]
+impl<C> From<ItemsPin<role::Items, C>> for PinMutex<C> {
+	fn from(items_pin: ItemsPin<role::Items, C>) -> Self {
+		Self::new(items_pin)
+	}
+}
+
+#[cfg(feature = "std")]
+impl<C> Debug for PinMutex<C>
+where
+	ItemsPin<role::Items, C>: Debug,
+{
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("PinMutex")
+			.field("items_pin", &self.items_pin)
+			.finish()
+	}
+}
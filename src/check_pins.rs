@@ -0,0 +1,244 @@
+//! Runtime checks of the pinning invariant (with the `"check-pins"` feature).
+//!
+//! The first time the items of an [`ItemsPin`] are observed pinned (through [`ItemsPinned::items_pinned`](`crate::ItemsPinned::items_pinned`),
+//! [`ItemsPinnedMut::items_pinned_mut`](`crate::ItemsPinnedMut::items_pinned_mut`) or indexed access like `.get_pinned`),
+//! their addresses are recorded. Each later pinned access, in-place drop like `.truncate_pinned`,
+//! [`ItemsPin::inner_mut_unchecked`](`crate::ItemsPin::inner_mut_unchecked`) and dropping the [`ItemsPin`] then compares against that record
+//! and panics, naming the collection type and item index,
+//! - if a recorded item is gone while items at new addresses have appeared, which means that items were moved
+//!   (e.g. by a [`Vec::push`](`alloc::vec::Vec::push`) that reallocated) instead of being dropped in place, or
+//! - when dropping the [`ItemsPin`], if a recorded item is gone even though the collection was only changed
+//!   through pinning methods (which update the record when they drop items in place) since it was recorded,
+//!   which means that the item was moved out or forgotten before its memory was freed.
+//!
+//! After [`ItemsPin::inner_mut_unchecked`](`crate::ItemsPin::inner_mut_unchecked`), items that are gone without new ones appearing
+//! are assumed to have been dropped in place through the returned reference.
+//!
+//! Only addresses are compared, so moves within the same storage (like [`slice::swap`]) aren't detected.
+//! If all items of a collection moved at once (e.g. when it's only borrowed as [`ItemsPin`] and its owner reallocates it),
+//! that's only detected after [`ItemsPin::inner_mut_unchecked`], as it looks the same as a different collection of the same type
+//! taking the place of one that was moved away.
+//!
+//! [`ItemsPin<R, C>`](`crate::ItemsPin`) is `#[repr(transparent)]`, so records are kept by collection address and type.
+//! Moving a collection doesn't move its items, so a record is found through its items when the collection is next accessed elsewhere.
+//! Records are discarded when their [`ItemsPin`] is dropped or unwrapped, and whenever moving the items becomes allowed,
+//! i.e. when the items are [`Unpin`] and are accessed mutably.
+//! Collections of zero-sized items aren't tracked.
+
+use crate::ItemsPin;
+use core::{
+	any::type_name,
+	sync::atomic::{AtomicUsize, Ordering},
+};
+use std::{
+	collections::{HashMap, HashSet},
+	sync::{Mutex, MutexGuard, PoisonError},
+	thread,
+	vec::Vec,
+};
+
+/// Reads the current item addresses of the collection at the given address, which must be of the registered type.
+type ItemAddresses = unsafe fn(usize) -> Vec<usize>;
+
+struct Record {
+	/// The type name of the collection, for panic messages.
+	collection: &'static str,
+	/// Item addresses, by index.
+	items: Vec<usize>,
+	/// Whether [`ItemsPin::inner_mut_unchecked`] was called since the items were recorded.
+	unchecked: bool,
+}
+
+#[derive(Default)]
+struct Registry {
+	/// Keyed by collection address and [`ItemsPin`] type name.
+	records: HashMap<(usize, &'static str), Record>,
+	/// Collection addresses, keyed by item address and [`ItemsPin`] type name.
+	owners: HashMap<(usize, &'static str), usize>,
+	/// Keyed by [`ItemsPin`] type name.
+	item_addresses: HashMap<&'static str, ItemAddresses>,
+}
+
+static REGISTRY: Mutex<Option<Registry>> = Mutex::new(None);
+
+/// The number of entries in [`Registry::records`], so that drops don't lock the registry while there are none.
+static RECORD_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+fn address<T: ?Sized>(value: &T) -> usize {
+	(value as *const T).cast::<()>() as usize
+}
+
+fn registry() -> MutexGuard<'static, Option<Registry>> {
+	REGISTRY.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl Registry {
+	fn insert(&mut self, key: (usize, &'static str), record: Record) {
+		for &item in &record.items {
+			self.owners.insert((item, key.1), key.0);
+		}
+		if self.records.insert(key, record).is_none() {
+			RECORD_COUNT.fetch_add(1, Ordering::Release);
+		}
+	}
+
+	fn remove(&mut self, key: (usize, &'static str)) -> Option<Record> {
+		let record = self.records.remove(&key)?;
+		RECORD_COUNT.fetch_sub(1, Ordering::Release);
+		for &item in &record.items {
+			if self.owners.get(&(item, key.1)) == Some(&key.0) {
+				self.owners.remove(&(item, key.1));
+			}
+		}
+		Some(record)
+	}
+
+	/// Removes and returns the record of the collection at `address` with the given `items`,
+	/// which may have been made at another address before the collection was moved.
+	///
+	/// A record at `address` that can't belong to the collection is discarded.
+	fn take(&mut self, address: usize, items_pin: &'static str, items: &[usize]) -> Option<Record> {
+		if let Some(record) = self.remove((address, items_pin)) {
+			// Otherwise, this is another collection that took the place of the recorded one.
+			if record.unchecked
+				|| record.items.is_empty()
+				|| items.is_empty()
+				|| items.iter().any(|item| record.items.contains(item))
+			{
+				return Some(record);
+			}
+		}
+
+		let owner = items
+			.iter()
+			.find_map(|&item| self.owners.get(&(item, items_pin)))
+			.copied()?;
+		self.remove((owner, items_pin))
+	}
+}
+
+/// Makes [`check_on_drop`] and [`forget`] work for `ItemsPin<R, C>`.
+///
+/// # Safety
+///
+/// `item_addresses` must read the item addresses of an `ItemsPin<R, C>` at the given address.
+pub(crate) unsafe fn register<R, C>(item_addresses: ItemAddresses) {
+	registry()
+		.get_or_insert_with(Registry::default)
+		.item_addresses
+		.entry(type_name::<ItemsPin<R, C>>())
+		.or_insert(item_addresses);
+}
+
+/// Checks `items_pin`'s current `items` against its record, if any, and then records them.
+///
+/// With `unchecked`, items that are gone at the next check are assumed to have been dropped in place.
+///
+/// # Panics
+///
+/// Iff a recorded item is gone while items at new addresses have appeared.
+pub(crate) fn observe<R, C: ?Sized>(
+	items_pin: &ItemsPin<R, C>,
+	items: Vec<usize>,
+	unchecked: bool,
+) {
+	let key = (address(items_pin), type_name::<ItemsPin<R, C>>());
+	let mut guard = registry();
+	let registry = guard.get_or_insert_with(Registry::default);
+	let record = registry.take(key.0, key.1, &items);
+	let moved = record.as_ref().and_then(|record| moved(record, &items));
+	registry.insert(
+		key,
+		Record {
+			collection: type_name::<C>(),
+			items,
+			unchecked,
+		},
+	);
+	// Unlocked before panicking.
+	drop(guard);
+
+	if let (Some(record), Some((index, address))) = (record, moved) {
+		panic!(
+			"napje check-pins: item {index} of `{}` was pinned at {address:#x}, but has since been moved instead of being dropped in place. Pinned items must stay in place until dropped.",
+			record.collection,
+		)
+	}
+}
+
+/// Removes the record of the collection at `items_pin`, along with its current item addresses if they can be read.
+fn take<R, C: ?Sized>(items_pin: &ItemsPin<R, C>) -> Option<(Record, Option<Vec<usize>>)> {
+	if RECORD_COUNT.load(Ordering::Acquire) == 0 {
+		return None;
+	}
+
+	let key = (address(items_pin), type_name::<ItemsPin<R, C>>());
+	let item_addresses = registry().as_ref()?.item_addresses.get(key.1).copied();
+	// Called without holding the lock, as this runs `Items::items`.
+	let items = item_addresses.map(|item_addresses| unsafe { item_addresses(key.0) });
+
+	let mut registry = registry();
+	let registry = registry.as_mut()?;
+	let record = match &items {
+		Some(items) => registry.take(key.0, key.1, items),
+		None => registry.remove(key),
+	}?;
+	Some((record, items))
+}
+
+/// Checks `items_pin` against its record, if any, right before it's dropped, and discards the record.
+///
+/// Skipped while the thread is panicking, to avoid aborting.
+///
+/// # Panics
+///
+/// Iff a recorded item is gone while items at new addresses have appeared,
+/// or if a recorded item is gone even though there was no unchecked access since it was recorded.
+pub(crate) fn check_on_drop<R, C: ?Sized>(items_pin: &ItemsPin<R, C>) {
+	let Some((record, Some(items))) = take(items_pin) else {
+		return;
+	};
+	if thread::panicking() {
+		return;
+	}
+
+	if let Some((index, address)) = moved(&record, &items) {
+		panic!(
+			"napje check-pins: item {index} of `{}` was pinned at {address:#x}, but has since been moved instead of being dropped in place. Pinned items must stay in place until dropped.",
+			record.collection,
+		)
+	}
+	if !record.unchecked {
+		if let Some((index, address)) = gone(&record, &items).next() {
+			panic!(
+				"napje check-pins: item {index} of `{}` was pinned at {address:#x}, but is gone without having been dropped in place before its memory was freed. Pinned items must stay in place until dropped.",
+				record.collection,
+			)
+		}
+	}
+}
+
+/// Discards `items_pin`'s record, if any, as its items are about to be moved or may be moved from now on.
+pub(crate) fn forget<R, C: ?Sized>(items_pin: &ItemsPin<R, C>) {
+	take(items_pin);
+}
+
+/// Recorded items that aren't among `items`, with their index.
+fn gone<'a>(record: &'a Record, items: &[usize]) -> impl 'a + Iterator<Item = (usize, usize)> {
+	let current: HashSet<usize> = items.iter().copied().collect();
+	record
+		.items
+		.iter()
+		.copied()
+		.enumerate()
+		.filter(move |(_, address)| !current.contains(address))
+}
+
+/// The first recorded item that's gone, with its index, iff items at new addresses have appeared too.
+fn moved(record: &Record, items: &[usize]) -> Option<(usize, usize)> {
+	let recorded: HashSet<usize> = record.items.iter().copied().collect();
+	if items.iter().all(|address| recorded.contains(address)) {
+		return None;
+	}
+	gone(record, items).next()
+}
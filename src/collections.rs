@@ -0,0 +1,43 @@
+//! Collection types that keep their items at stable addresses by design,
+//! so that they can accept new items even while those already inside are pinned.
+
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+pub mod concurrent_pinned_vec;
+#[cfg(feature = "alloc")]
+pub mod holey_vec;
+#[cfg(feature = "alloc")]
+pub mod pin_arena;
+pub mod pinned_array_vec;
+#[cfg(feature = "std")]
+pub mod pinned_hash_map;
+#[cfg(feature = "std")]
+pub mod pinned_hash_set;
+#[cfg(feature = "alloc")]
+pub mod pinned_heap;
+#[cfg(feature = "alloc")]
+pub mod pinned_ring;
+#[cfg(feature = "alloc")]
+pub mod reserved_pinned_vec;
+
+#[cfg(all(feature = "alloc", target_os = "linux"))]
+mod reservation;
+#[cfg(feature = "alloc")]
+mod slots;
+
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+pub use concurrent_pinned_vec::ConcurrentPinnedVec;
+#[cfg(feature = "alloc")]
+pub use holey_vec::HoleyVec;
+#[cfg(feature = "alloc")]
+pub use pin_arena::PinArena;
+pub use pinned_array_vec::PinnedArrayVec;
+#[cfg(feature = "std")]
+pub use pinned_hash_map::PinnedHashMap;
+#[cfg(feature = "std")]
+pub use pinned_hash_set::PinnedHashSet;
+#[cfg(feature = "alloc")]
+pub use pinned_heap::PinnedHeap;
+#[cfg(feature = "alloc")]
+pub use pinned_ring::PinnedRing;
+#[cfg(feature = "alloc")]
+pub use reserved_pinned_vec::ReservedPinnedVec;
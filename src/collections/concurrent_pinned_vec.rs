@@ -0,0 +1,283 @@
+//! An append-only vector that can be pushed to concurrently through a shared reference.
+//!
+//! [`ConcurrentPinnedVec<T>`] stores its items in buckets of doubling size that are allocated on demand.
+//! Buckets are never reallocated, so each item stays in place from [`.push_pinned`](`ConcurrentPinnedVec::push_pinned`) until the vector is dropped.
+//!
+//! Since there is no way to move items out again, all items are considered pinned at all times.
+//! Accordingly, [`ConcurrentPinnedVec<T>`] implements [`ItemsPinned`] directly
+//! and does not need to be wrapped in an [`ItemsPin`](`crate::ItemsPin`).
+
+use crate::{role, Items, ItemsDebug, ItemsPinned, PinIter};
+use alloc::boxed::Box;
+use core::{
+	cell::UnsafeCell,
+	fmt::{self, Debug, Formatter},
+	marker::PhantomData,
+	mem::{self, MaybeUninit},
+	pin::Pin,
+	ptr,
+	sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering},
+};
+
+const FIRST_BUCKET_LEN_LOG2: usize = 5;
+const FIRST_BUCKET_LEN: usize = 1 << FIRST_BUCKET_LEN_LOG2;
+const USIZE_BITS: usize = mem::size_of::<usize>() * 8;
+const BUCKET_COUNT: usize = USIZE_BITS - FIRST_BUCKET_LEN_LOG2;
+
+struct Slot<T> {
+	published: AtomicBool,
+	value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// Locates `index` as `(bucket, bucket_len, offset)`.
+///
+/// # Panics
+///
+/// Iff `index` is too large to be addressed.
+fn locate(index: usize) -> (usize, usize, usize) {
+	let shifted = index
+		.checked_add(FIRST_BUCKET_LEN)
+		.expect("`ConcurrentPinnedVec` capacity overflow");
+	let bucket = USIZE_BITS - 1 - shifted.leading_zeros() as usize - FIRST_BUCKET_LEN_LOG2;
+	let bucket_len = FIRST_BUCKET_LEN << bucket;
+	(bucket, bucket_len, shifted - bucket_len)
+}
+
+/// An append-only vector of pinned items that can be pushed to through `&self`.
+///
+/// Pushing is lock-free:
+/// Each push reserves an index with a single atomic increment,
+/// allocates that index's bucket if necessary (racing threads discard their spare allocation)
+/// and then publishes the item, at which point it becomes visible to readers.
+///
+/// Items are dropped in place when the vector is dropped.
+pub struct ConcurrentPinnedVec<T> {
+	reserved: AtomicUsize,
+	buckets: [AtomicPtr<Slot<T>>; BUCKET_COUNT],
+	_phantom: PhantomData<T>,
+}
+
+unsafe impl<T: Send> Send for ConcurrentPinnedVec<T> {}
+unsafe impl<T: Send + Sync> Sync for ConcurrentPinnedVec<T> {}
+
+impl<T> ConcurrentPinnedVec<T> {
+	#[allow(clippy::declare_interior_mutable_const)] // Only used as array initialiser.
+	const NO_BUCKET: AtomicPtr<Slot<T>> = AtomicPtr::new(ptr::null_mut());
+
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			reserved: AtomicUsize::new(0),
+			buckets: [Self::NO_BUCKET; BUCKET_COUNT],
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Appends `value` and returns its index.
+	///
+	/// Existing items are never moved by this.
+	///
+	/// # Panics
+	///
+	/// Iff the index space is exhausted.
+	pub fn push_pinned(&self, value: T) -> usize {
+		let index = self.reserved.fetch_add(1, Ordering::Relaxed);
+		let (bucket, bucket_len, offset) = locate(index);
+		let slot = unsafe { &*self.bucket_or_allocate(bucket, bucket_len).add(offset) };
+		unsafe { slot.value.get().cast::<T>().write(value) };
+		slot.published.store(true, Ordering::Release);
+		index
+	}
+
+	fn bucket_or_allocate(&self, bucket: usize, bucket_len: usize) -> *mut Slot<T> {
+		let existing = self.buckets[bucket].load(Ordering::Acquire);
+		if !existing.is_null() {
+			return existing;
+		}
+
+		let allocated = Box::into_raw(
+			(0..bucket_len)
+				.map(|_| Slot {
+					published: AtomicBool::new(false),
+					value: UnsafeCell::new(MaybeUninit::uninit()),
+				})
+				.collect::<Box<[Slot<T>]>>(),
+		)
+		.cast::<Slot<T>>();
+		match self.buckets[bucket].compare_exchange(
+			ptr::null_mut(),
+			allocated,
+			Ordering::AcqRel,
+			Ordering::Acquire,
+		) {
+			Ok(_) => allocated,
+			Err(winner) => {
+				drop(unsafe {
+					Box::from_raw(ptr::slice_from_raw_parts_mut(allocated, bucket_len))
+				});
+				winner
+			}
+		}
+	}
+
+	/// Locates the item at `index` iff it has been published.
+	fn get_raw(&self, index: usize) -> Option<*mut T> {
+		if index >= self.reserved.load(Ordering::Relaxed) {
+			return None;
+		}
+
+		let (bucket, _, offset) = locate(index);
+		let bucket = self.buckets[bucket].load(Ordering::Acquire);
+		if bucket.is_null() {
+			return None;
+		}
+
+		let slot = unsafe { &*bucket.add(offset) };
+		if slot.published.load(Ordering::Acquire) {
+			Some(slot.value.get().cast())
+		} else {
+			None
+		}
+	}
+
+	/// Retrieves the item at `index`,
+	/// or [`None`] iff it hasn't been published (yet).
+	#[must_use]
+	pub fn get(&self, index: usize) -> Option<&T> {
+		self.get_raw(index).map(|item| unsafe { &*item })
+	}
+
+	/// Retrieves the item at `index` as pinned reference,
+	/// or [`None`] iff it hasn't been published (yet).
+	#[must_use]
+	pub fn get_pinned(&self, index: usize) -> Option<Pin<&T>> {
+		self.get(index)
+			.map(|item| unsafe { Pin::new_unchecked(item) })
+	}
+
+	/// Retrieves the item at `index` as pinned mutable reference,
+	/// or [`None`] iff it hasn't been published (yet).
+	pub fn get_pinned_mut(&mut self, index: usize) -> Option<Pin<&mut T>> {
+		self.get_raw(index)
+			.map(|item| unsafe { Pin::new_unchecked(&mut *item) })
+	}
+
+	/// The number of reserved indices.
+	///
+	/// While pushes are in progress on other threads,
+	/// this may include items that haven't been published yet.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.reserved.load(Ordering::Acquire)
+	}
+
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Iterates over all published items in index order.
+	///
+	/// Items that are published after this call may or may not be visited.
+	#[must_use]
+	pub fn iter(&self) -> Iter<'_, T> {
+		Iter {
+			vec: self,
+			index: 0,
+			end: self.len(),
+		}
+	}
+}
+
+impl<T> Default for ConcurrentPinnedVec<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> Drop for ConcurrentPinnedVec<T> {
+	fn drop(&mut self) {
+		let len = *self.reserved.get_mut();
+		for (bucket, slots) in self.buckets.iter_mut().enumerate() {
+			let slots = *slots.get_mut();
+			if slots.is_null() {
+				continue;
+			}
+
+			let bucket_len = FIRST_BUCKET_LEN << bucket;
+			let bucket_start = bucket_len - FIRST_BUCKET_LEN;
+			let slots = unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(slots, bucket_len)) };
+			for slot in slots.iter().take(len.saturating_sub(bucket_start)) {
+				if slot.published.load(Ordering::Relaxed) {
+					unsafe { ptr::drop_in_place(slot.value.get().cast::<T>()) }
+				}
+			}
+		}
+	}
+}
+
+impl<T: Debug> Debug for ConcurrentPinnedVec<T> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_list().entries(self.iter()).finish()
+	}
+}
+
+impl<'a, T> IntoIterator for &'a ConcurrentPinnedVec<T> {
+	type Item = &'a T;
+	type IntoIter = Iter<'a, T>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+/// An iterator over the published items of a [`ConcurrentPinnedVec<T>`].
+pub struct Iter<'a, T> {
+	vec: &'a ConcurrentPinnedVec<T>,
+	index: usize,
+	end: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+	type Item = &'a T;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		while self.index < self.end {
+			let index = self.index;
+			self.index += 1;
+			if let Some(item) = self.vec.get(index) {
+				return Some(item);
+			}
+		}
+		None
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(0, Some(self.end - self.index))
+	}
+}
+
+impl<T> Items<role::Items> for ConcurrentPinnedVec<T> {
+	type Item = T;
+	type ItemsIter<'a>
+		= Iter<'a, T>
+	where
+		Self: 'a;
+
+	fn items(&self) -> Self::ItemsIter<'_> {
+		self.iter()
+	}
+}
+
+impl<T> ItemsDebug<role::Items> for ConcurrentPinnedVec<T> {}
+
+impl<T> ItemsPinned<role::Items> for ConcurrentPinnedVec<T> {
+	type ItemsPinnedIter<'a>
+		= PinIter<Iter<'a, T>>
+	where
+		Self: 'a;
+
+	fn items_pinned(&self) -> Self::ItemsPinnedIter<'_> {
+		unsafe { PinIter::new_unchecked(self.iter()) }
+	}
+}
@@ -0,0 +1,293 @@
+//! A vector with [`role::Items`] that leaves holes where items are removed, instead of moving the items after them.
+//!
+//! [`HoleyVec<T>`] stores its items in stable chunked slots, so it can be pinned via [`ItemsPin`]
+//! and still accept new items and drop arbitrary ones afterwards.
+//! Item indices stay valid until that item is removed.
+//! Iteration skips holes, and new items fill the most recently vacated hole first.
+//!
+//! The added [`ItemsPin<role::Items, HoleyVec<T>>`](`self`) methods are:
+//!
+//! - `.close_all_pinned` (for items that implement [`PollClose`]), which closes all items concurrently
+//!   and drops each in place as soon as it is closed.
+//! - `.get_pinned` and `.get_pinned_mut`, which return the item at an index, if there is one.
+//! - `.insert_pinned`, which stores an item without moving any others and returns its index.
+//! - `.remove_pinned`, which drops an item in place if present, returning [`bool`].
+//! - `.retain_pinned`, which drops each item for which a predicate on [`Pin<&mut T>`] returns `false`, in index order.
+
+use super::slots::{self, Slots};
+use crate::{future::PollClose, role, Items, ItemsDebug, ItemsMut, ItemsPin};
+use core::{
+	fmt::{self, Debug, DebugStruct, Formatter},
+	future::Future,
+	pin::Pin,
+	task::{Context, Poll},
+};
+
+/// A vector of items that keep their index (and address) until removed.
+///
+/// Items are dropped in place when the vector is dropped.
+pub struct HoleyVec<T> {
+	slots: Slots<T>,
+}
+
+impl<T> HoleyVec<T> {
+	#[must_use]
+	pub const fn new() -> Self {
+		Self {
+			slots: Slots::new(),
+		}
+	}
+
+	/// The number of items, not counting holes.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.slots.len()
+	}
+
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// One past the highest index that has been used so far.
+	#[must_use]
+	pub fn end(&self) -> usize {
+		self.slots.end()
+	}
+
+	/// The index that the next inserted item will get.
+	#[must_use]
+	pub fn next_index(&self) -> usize {
+		self.slots.next_index()
+	}
+
+	#[must_use]
+	pub fn get(&self, index: usize) -> Option<&T> {
+		self.slots.get(index)
+	}
+
+	pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+		self.slots.get_mut(index)
+	}
+
+	/// Stores `value`, filling a hole if there is one, and returns its index.
+	pub fn insert(&mut self, value: T) -> usize {
+		self.slots.insert(value)
+	}
+
+	/// Removes and returns the item at `index`, leaving a hole.
+	pub fn remove(&mut self, index: usize) -> Option<T> {
+		self.slots.take(index)
+	}
+
+	/// Iterates over the items as `(index, &T)`, skipping holes.
+	#[must_use]
+	pub fn iter(&self) -> Iter<'_, T> {
+		Iter(self.slots.iter())
+	}
+
+	/// Iterates over the items as `(index, &mut T)`, skipping holes.
+	#[must_use]
+	pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+		IterMut(self.slots.iter_mut())
+	}
+}
+
+impl<T> Default for HoleyVec<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> Drop for HoleyVec<T> {
+	fn drop(&mut self) {
+		self.slots.clear_in_place()
+	}
+}
+
+/// Formats as a map from index to item.
+impl<T: Debug> Debug for HoleyVec<T> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_map().entries(self.iter()).finish()
+	}
+}
+
+impl<'a, T> IntoIterator for &'a HoleyVec<T> {
+	type Item = (usize, &'a T);
+	type IntoIter = Iter<'a, T>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+impl<'a, T> IntoIterator for &'a mut HoleyVec<T> {
+	type Item = (usize, &'a mut T);
+	type IntoIter = IterMut<'a, T>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter_mut()
+	}
+}
+
+/// Iterates over the items of a [`HoleyVec<T>`] as `(index, &T)`.
+pub struct Iter<'a, T>(slots::Iter<'a, T>);
+
+impl<'a, T> Iterator for Iter<'a, T> {
+	type Item = (usize, &'a T);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.next()
+	}
+}
+
+/// Iterates over the items of a [`HoleyVec<T>`] as `(index, &mut T)`.
+pub struct IterMut<'a, T>(slots::IterMut<'a, T>);
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+	type Item = (usize, &'a mut T);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.next()
+	}
+}
+
+/// Iterates over the items of a [`HoleyVec<T>`], skipping holes.
+pub struct Values<'a, T>(slots::Iter<'a, T>);
+
+impl<'a, T> Iterator for Values<'a, T> {
+	type Item = &'a T;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.next().map(|(_, value)| value)
+	}
+}
+
+/// Iterates mutably over the items of a [`HoleyVec<T>`], skipping holes.
+pub struct ValuesMut<'a, T>(slots::IterMut<'a, T>);
+
+impl<'a, T> Iterator for ValuesMut<'a, T> {
+	type Item = &'a mut T;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.next().map(|(_, value)| value)
+	}
+}
+
+impl<T> Items<role::Items> for HoleyVec<T> {
+	type Item = T;
+	type ItemsIter<'a>
+		= Values<'a, T>
+	where
+		Self: 'a;
+
+	fn items(&self) -> Self::ItemsIter<'_> {
+		Values(self.slots.iter())
+	}
+}
+
+impl<T> ItemsDebug<role::Items> for HoleyVec<T> {
+	/// Shows `len` and `end`.
+	fn debug_fields(&self, debug_struct: &mut DebugStruct) {
+		debug_struct
+			.field("len", &self.len())
+			.field("end", &self.end());
+	}
+}
+
+impl<T> ItemsMut<role::Items> for HoleyVec<T> {
+	type ItemsMutIter<'a>
+		= ValuesMut<'a, T>
+	where
+		Self: 'a;
+
+	fn items_mut(&mut self) -> Self::ItemsMutIter<'_> {
+		ValuesMut(self.slots.iter_mut())
+	}
+}
+
+impl<T> ItemsPin<role::Items, HoleyVec<T>> {
+	#[must_use]
+	pub fn pin(vec: HoleyVec<T>) -> Self {
+		// SAFETY: The items live in `Slots`, which never moves them, and removing one leaves a hole in its place.
+		// This API only drops items in place.
+		unsafe { ItemsPin::new_unchecked(vec) }
+	}
+
+	#[must_use]
+	pub fn get_pinned(&self, index: usize) -> Option<Pin<&T>> {
+		#[cfg(feature = "check-pins")]
+		self.check_pins();
+		self.collection
+			.get(index)
+			// SAFETY: The vector is pinned, and the item stays in its slot until dropped in place.
+			.map(|item| unsafe { Pin::new_unchecked(item) })
+	}
+
+	pub fn get_pinned_mut(&mut self, index: usize) -> Option<Pin<&mut T>> {
+		#[cfg(feature = "check-pins")]
+		self.check_pins();
+		self.collection
+			.get_mut(index)
+			// SAFETY: As above.
+			.map(|item| unsafe { Pin::new_unchecked(item) })
+	}
+
+	/// Stores `value` without moving any other items, filling a hole if there is one, and returns its index.
+	pub fn insert_pinned(&mut self, value: T) -> usize {
+		self.collection.insert(value)
+	}
+
+	/// Drops the item at `index` in place, leaving a hole.
+	pub fn remove_pinned(&mut self, index: usize) -> bool {
+		#[cfg(feature = "check-pins")]
+		self.check_pins();
+		let removed = self.collection.slots.remove_in_place(index);
+		#[cfg(feature = "check-pins")]
+		self.record_pins();
+		removed
+	}
+
+	/// Drops each item for which `keep` returns `false` in place, leaving holes.
+	///
+	/// Items are visited in index order, and each is dropped before the next is visited.
+	/// The surviving items are not moved.
+	pub fn retain_pinned(&mut self, mut keep: impl FnMut(Pin<&mut T>) -> bool) {
+		for index in 0..self.collection.end() {
+			if let Some(item) = self.get_pinned_mut(index) {
+				if !keep(item) {
+					self.remove_pinned(index);
+				}
+			}
+		}
+	}
+}
+
+impl<T: PollClose> ItemsPin<role::Items, HoleyVec<T>> {
+	/// Resolves once all items have reported that they are closed and have been dropped in place.
+	///
+	/// Items are polled in index order, and each is dropped as soon as it is closed.
+	pub fn close_all_pinned(&mut self) -> CloseAllPinned<'_, T> {
+		CloseAllPinned { vec: self }
+	}
+}
+
+/// Closes the items of an [`ItemsPin<role::Items, HoleyVec<T>>`] concurrently. See `.close_all_pinned`.
+#[must_use = "futures do nothing unless polled"]
+pub struct CloseAllPinned<'a, T> {
+	vec: &'a mut ItemsPin<role::Items, HoleyVec<T>>,
+}
+
+impl<T: PollClose> Future for CloseAllPinned<'_, T> {
+	type Output = ();
+
+	fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+		let vec = &mut *self.get_mut().vec;
+		vec.retain_pinned(|item| item.poll_close(cx).is_pending());
+		if vec.is_empty() {
+			Poll::Ready(())
+		} else {
+			Poll::Pending
+		}
+	}
+}
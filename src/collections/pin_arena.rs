@@ -0,0 +1,185 @@
+//! An arena with [`role::Items`] that allocates pinned items through a shared reference.
+//!
+//! [`PinArena<T>`] stores its items in chunks of doubling capacity that are never reallocated,
+//! so each item stays in place from [`.alloc`](`PinArena::alloc`) until the arena is dropped.
+//! This makes it a good fit for graph-shaped, self-referential data,
+//! where items refer to each other through the arena's lifetime.
+//!
+//! Since there is no way to move items out again, all items are considered pinned at all times.
+//! Accordingly, [`PinArena<T>`] implements [`ItemsPinned`] directly
+//! and does not need to be wrapped in an [`ItemsPin`](`crate::ItemsPin`).
+//!
+//! As all items can be visited through `&self` via [`Items`] at any time,
+//! [`.alloc`](`PinArena::alloc`) hands out shared references only.
+//! Use interior mutability (e.g. [`Cell`](`core::cell::Cell`)) for edges that are set after allocation,
+//! or [`.items_pinned_mut`](`PinArena::items_pinned_mut`) for exclusive access.
+//!
+//! `PinArena<T>` itself doesn't implement [`Drop`], so items may refer to each other with the arena's lifetime
+//! as long as they don't implement [`Drop`] either, just like with [`Vec<T>`].
+
+use crate::{role, Items, ItemsDebug, ItemsPinned, PinIter};
+use alloc::vec::Vec;
+use core::{
+	cell::UnsafeCell,
+	fmt::{self, Debug, DebugStruct, Formatter},
+	iter::Flatten,
+	pin::Pin,
+	slice,
+};
+
+const FIRST_CHUNK_CAPACITY: usize = 32;
+
+/// An append-only arena of pinned items that can be allocated into through `&self`.
+///
+/// [`.alloc`](`PinArena::alloc`) returns [`Pin<&T>`] rather than [`Pin<&mut T>`], as the handles alias:
+/// Each item stays reachable through `&self` (via [`.iter`](`PinArena::iter`) and [`ItemsPinned`])
+/// for as long as the arena is borrowed, so an exclusive reference to it could coexist with shared ones.
+/// [`.items_pinned_mut`](`PinArena::items_pinned_mut`) hands out [`Pin<&mut T>`] through `&mut self` instead.
+///
+/// Items are dropped in place when the arena is dropped, in allocation order.
+pub struct PinArena<T> {
+	/// Only pushed to within capacity, except for the outer [`Vec`].
+	chunks: UnsafeCell<Vec<Vec<T>>>,
+}
+
+impl<T> PinArena<T> {
+	#[must_use]
+	pub const fn new() -> Self {
+		Self {
+			chunks: UnsafeCell::new(Vec::new()),
+		}
+	}
+
+	/// Moves `value` into the arena and returns it pinned for as long as the arena is borrowed.
+	///
+	/// Existing items are never moved by this.
+	pub fn alloc(&self, value: T) -> Pin<&T> {
+		// No other references to the outer `Vec` exist outside of this crate,
+		// and no user code runs while this one is alive.
+		let chunks = unsafe { &mut *self.chunks.get() };
+
+		let new_capacity = match chunks.last() {
+			Some(chunk) if chunk.len() < chunk.capacity() => None,
+			Some(chunk) => Some(chunk.capacity() * 2),
+			None => Some(FIRST_CHUNK_CAPACITY),
+		};
+		if let Some(capacity) = new_capacity {
+			chunks.push(Vec::with_capacity(capacity));
+		}
+
+		let Some(chunk) = chunks.last_mut() else {
+			unreachable!()
+		};
+		// Within capacity, so this doesn't reallocate.
+		chunk.push(value);
+		unsafe { Pin::new_unchecked(&*chunk.as_ptr().add(chunk.len() - 1)) }
+	}
+
+	#[must_use]
+	pub fn len(&self) -> usize {
+		unsafe { &*self.chunks.get() }.iter().map(Vec::len).sum()
+	}
+
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Iterates over all items in allocation order.
+	///
+	/// Items that are allocated during iteration are visited too.
+	#[must_use]
+	pub fn iter(&self) -> Iter<'_, T> {
+		Iter {
+			arena: self,
+			chunk: 0,
+			offset: 0,
+		}
+	}
+
+	/// Iterates mutably over all items in allocation order.
+	#[must_use]
+	pub fn items_pinned_mut(&mut self) -> PinIter<Flatten<slice::IterMut<'_, Vec<T>>>> {
+		unsafe { PinIter::new_unchecked(self.chunks.get_mut().iter_mut().flatten()) }
+	}
+}
+
+impl<T> Default for PinArena<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Debug> Debug for PinArena<T> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_list().entries(self.iter()).finish()
+	}
+}
+
+impl<'a, T> IntoIterator for &'a PinArena<T> {
+	type Item = &'a T;
+	type IntoIter = Iter<'a, T>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+/// Iterates over the items of a [`PinArena<T>`] in allocation order.
+pub struct Iter<'a, T> {
+	arena: &'a PinArena<T>,
+	chunk: usize,
+	offset: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+	type Item = &'a T;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		// Only borrowed briefly, as `.alloc` may be called between calls to `.next`.
+		let chunks = unsafe { &*self.arena.chunks.get() };
+		loop {
+			let chunk = chunks.get(self.chunk)?;
+			if self.offset < chunk.len() {
+				let item = unsafe { &*chunk.as_ptr().add(self.offset) };
+				self.offset += 1;
+				return Some(item);
+			} else if self.offset < chunk.capacity() {
+				return None;
+			}
+
+			self.chunk += 1;
+			self.offset = 0;
+		}
+	}
+}
+
+impl<T> Items<role::Items> for PinArena<T> {
+	type Item = T;
+	type ItemsIter<'a>
+		= Iter<'a, T>
+	where
+		Self: 'a;
+
+	fn items(&self) -> Self::ItemsIter<'_> {
+		self.iter()
+	}
+}
+
+impl<T> ItemsDebug<role::Items> for PinArena<T> {
+	/// Shows `len`.
+	fn debug_fields(&self, debug_struct: &mut DebugStruct) {
+		debug_struct.field("len", &self.len());
+	}
+}
+
+impl<T> ItemsPinned<role::Items> for PinArena<T> {
+	type ItemsPinnedIter<'a>
+		= PinIter<Iter<'a, T>>
+	where
+		Self: 'a;
+
+	fn items_pinned(&self) -> Self::ItemsPinnedIter<'_> {
+		unsafe { PinIter::new_unchecked(self.iter()) }
+	}
+}
@@ -0,0 +1,189 @@
+//! A fixed-capacity vector that stores its items inline, without allocating.
+//!
+//! Since the items move along with the [`PinnedArrayVec<T, N>`] itself,
+//! it has to be pinned as a whole (e.g. via [`Box::pin`](`alloc::boxed::Box::pin`) or [`core::pin::pin!`])
+//! before its items count as pinned.
+//! This results in a two-phase API:
+//!
+//! 1. Before pinning, it behaves like a plain array-backed vector,
+//!    with `.push`, `.pop`, `.as_slice` and `.as_mut_slice`.
+//! 2. Through [`Pin<&mut PinnedArrayVec<T, N>>`], the added methods are:
+//!
+//!    - `.as_pinned_slice` and `.as_pinned_mut_slice`, which return [`&ItemsPin<role::Items, [T]>`](`crate::std_impls::slice`).
+//!    - `.items_pinned` and `.items_pinned_mut`, which iterate over the items as [`Pin`]s.
+//!    - `.pop_pinned`, which drops the last value in place if possible, returning [`bool`].
+//!    - `.push_pinned`, which fails only if the buffer is full.
+//!    - `.truncate_pinned`, which drops items in place from the end.
+//!
+//! Don't wrap a [`PinnedArrayVec<T, N>`] in an [`ItemsPin`], as that doesn't keep the items in place.
+
+use crate::{role, Items, ItemsDebug, ItemsMut, ItemsPin, PinIter};
+use core::{
+	fmt::{self, Debug, Formatter},
+	mem::MaybeUninit,
+	pin::Pin,
+	ptr,
+	slice::{self, Iter, IterMut},
+};
+
+/// A vector of up to `N` items stored inline, which can be pinned as a whole.
+///
+/// Items are dropped in place when the vector is dropped.
+pub struct PinnedArrayVec<T, const N: usize> {
+	len: usize,
+	items: [MaybeUninit<T>; N],
+}
+
+impl<T, const N: usize> PinnedArrayVec<T, N> {
+	#[must_use]
+	pub const fn new() -> Self {
+		Self {
+			len: 0,
+			// An array of `MaybeUninit`s doesn't need initialisation.
+			items: unsafe { MaybeUninit::uninit().assume_init() },
+		}
+	}
+
+	#[must_use]
+	pub const fn capacity(&self) -> usize {
+		N
+	}
+
+	#[must_use]
+	pub const fn len(&self) -> usize {
+		self.len
+	}
+
+	#[must_use]
+	pub const fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	#[must_use]
+	pub fn as_slice(&self) -> &[T] {
+		unsafe { slice::from_raw_parts(self.items.as_ptr().cast(), self.len) }
+	}
+
+	#[must_use]
+	pub fn as_mut_slice(&mut self) -> &mut [T] {
+		unsafe { slice::from_raw_parts_mut(self.items.as_mut_ptr().cast(), self.len) }
+	}
+
+	/// # Errors
+	///
+	/// Iff the buffer is full.
+	pub fn push(&mut self, value: T) -> Result<(), T> {
+		match self.items.get_mut(self.len) {
+			Some(slot) => {
+				*slot = MaybeUninit::new(value);
+				self.len += 1;
+				Ok(())
+			}
+			None => Err(value),
+		}
+	}
+
+	pub fn pop(&mut self) -> Option<T> {
+		self.len = self.len.checked_sub(1)?;
+		Some(unsafe { self.items[self.len].as_ptr().read() })
+	}
+
+	/// # Errors
+	///
+	/// Iff the buffer is full.
+	pub fn push_pinned(self: Pin<&mut Self>, value: T) -> Result<(), T> {
+		// Writing into a free slot doesn't move any existing items.
+		unsafe { self.get_unchecked_mut() }.push(value)
+	}
+
+	#[allow(clippy::must_use_candidate)] // Clippy doesn't see through `Pin<&mut Self>`.
+	pub fn pop_pinned(self: Pin<&mut Self>) -> bool {
+		!self.is_empty() && {
+			let len = self.len - 1;
+			self.truncate_pinned(len);
+			true
+		}
+	}
+
+	/// Drops all items past `len` in place.
+	///
+	/// Does nothing iff `len` is greater than or equal to the current length.
+	pub fn truncate_pinned(self: Pin<&mut Self>, len: usize) {
+		let this = unsafe { self.get_unchecked_mut() };
+		if len >= this.len {
+			return;
+		}
+
+		let tail = ptr::slice_from_raw_parts_mut(
+			this.items[len..].as_mut_ptr().cast::<T>(),
+			this.len - len,
+		);
+		// Shorten first, so that a panicking destructor leaks instead of double-dropping.
+		this.len = len;
+		unsafe { ptr::drop_in_place(tail) }
+	}
+
+	#[must_use]
+	pub fn as_pinned_slice(self: Pin<&Self>) -> &ItemsPin<role::Items, [T]> {
+		unsafe { &*(Pin::get_ref(self).as_slice() as *const [T] as *const _) }
+	}
+
+	#[must_use]
+	pub fn as_pinned_mut_slice(self: Pin<&mut Self>) -> &mut ItemsPin<role::Items, [T]> {
+		unsafe { &mut *(self.get_unchecked_mut().as_mut_slice() as *mut [T] as *mut _) }
+	}
+
+	#[must_use]
+	pub fn items_pinned(self: Pin<&Self>) -> PinIter<Iter<'_, T>> {
+		unsafe { PinIter::new_unchecked(Pin::get_ref(self).as_slice().iter()) }
+	}
+
+	#[must_use]
+	pub fn items_pinned_mut(self: Pin<&mut Self>) -> PinIter<IterMut<'_, T>> {
+		unsafe { PinIter::new_unchecked(self.get_unchecked_mut().as_mut_slice().iter_mut()) }
+	}
+}
+
+impl<T, const N: usize> Default for PinnedArrayVec<T, N> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T, const N: usize> Drop for PinnedArrayVec<T, N> {
+	fn drop(&mut self) {
+		// The vector may have been pinned, so this has to happen in place either way.
+		unsafe { Pin::new_unchecked(self) }.truncate_pinned(0)
+	}
+}
+
+impl<T: Debug, const N: usize> Debug for PinnedArrayVec<T, N> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_list().entries(self.as_slice()).finish()
+	}
+}
+
+impl<T, const N: usize> Items<role::Items> for PinnedArrayVec<T, N> {
+	type Item = T;
+	type ItemsIter<'a>
+		= Iter<'a, T>
+	where
+		Self: 'a;
+
+	fn items(&self) -> Self::ItemsIter<'_> {
+		self.as_slice().iter()
+	}
+}
+
+impl<T, const N: usize> ItemsDebug<role::Items> for PinnedArrayVec<T, N> {}
+
+impl<T, const N: usize> ItemsMut<role::Items> for PinnedArrayVec<T, N> {
+	type ItemsMutIter<'a>
+		= IterMut<'a, T>
+	where
+		Self: 'a;
+
+	fn items_mut(&mut self) -> Self::ItemsMutIter<'_> {
+		self.as_mut_slice().iter_mut()
+	}
+}
@@ -0,0 +1,403 @@
+//! A hash map with [`role::Keys`], [`role::Values`] and [`role::Entries`] that keeps its entries in place while growing.
+//!
+//! [`PinnedHashMap<K, V, S>`] stores its entries in stable chunked slots and only moves slot indices around in its hash table,
+//! so its values can be pinned via [`ItemsPin<role::Values, _>`](`ItemsPin`) and it still accepts new entries afterwards.
+//!
+//! Keys can't be mutated through the map, as that could change their hashes.
+//! Values can be, and [`ItemsMut<role::Values>`] is implemented accordingly.
+//!
+//! The added [`ItemsPin<role::Values, PinnedHashMap<K, V, S>>`](`self`) methods are:
+//!
+//! - `.get_pinned` and `.get_pinned_mut`, which look up a value by any borrowed form of its key.
+//! - `.insert_pinned`, which drops any previous entry with an equal key in place and returns the new value pinned.
+//! - `.remove`, which drops an entry in place if present, returning [`bool`].
+
+use super::{
+	pinned_hash_set::make_hash,
+	slots::{self, Slots},
+};
+use crate::{role, Items, ItemsDebug, ItemsMut, ItemsPin};
+use core::{
+	borrow::Borrow,
+	fmt::{self, Debug, DebugStruct, Formatter},
+	hash::{BuildHasher, Hash},
+	mem,
+	pin::Pin,
+};
+use hashbrown::HashTable;
+use std::collections::hash_map::RandomState;
+
+/// A hash map whose entries don't move once inserted.
+///
+/// Iteration order is unspecified, but the same for all roles.
+/// Entries are dropped in place when the map is dropped.
+pub struct PinnedHashMap<K, V, S = RandomState> {
+	slots: Slots<(K, V)>,
+	/// Indices into `slots`.
+	table: HashTable<usize>,
+	hash_builder: S,
+}
+
+impl<K, V> PinnedHashMap<K, V> {
+	#[must_use]
+	pub fn new() -> Self {
+		Self::with_hasher(RandomState::new())
+	}
+}
+
+impl<K, V, S> PinnedHashMap<K, V, S> {
+	#[must_use]
+	pub const fn with_hasher(hash_builder: S) -> Self {
+		Self {
+			slots: Slots::new(),
+			table: HashTable::new(),
+			hash_builder,
+		}
+	}
+
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.slots.len()
+	}
+
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	#[must_use]
+	pub fn hasher(&self) -> &S {
+		&self.hash_builder
+	}
+
+	#[must_use]
+	pub fn iter(&self) -> Iter<'_, K, V> {
+		Iter(self.slots.iter())
+	}
+
+	#[must_use]
+	pub fn keys(&self) -> Keys<'_, K, V> {
+		Keys(self.slots.iter())
+	}
+
+	#[must_use]
+	pub fn values(&self) -> Values<'_, K, V> {
+		Values(self.slots.iter())
+	}
+
+	#[must_use]
+	pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+		ValuesMut(self.slots.iter_mut())
+	}
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> PinnedHashMap<K, V, S> {
+	fn find<Q: ?Sized + Hash + Eq>(&self, key: &Q) -> Option<usize>
+	where
+		K: Borrow<Q>,
+	{
+		let slots = &self.slots;
+		self.table
+			.find(
+				make_hash(&self.hash_builder, key),
+				|&index| matches!(slots.get(index), Some((k, _)) if k.borrow() == key),
+			)
+			.copied()
+	}
+
+	/// Stores a new entry, which must not have a key equal to any existing one.
+	fn insert_unique(&mut self, key: K, value: V) -> usize {
+		let hash = make_hash(&self.hash_builder, &key);
+		let Self {
+			slots,
+			table,
+			hash_builder,
+		} = self;
+
+		let index = slots.insert((key, value));
+		table.insert_unique(hash, index, |&index| {
+			make_hash(
+				hash_builder,
+				&slots.get(index).expect("dangling slot index").0,
+			)
+		});
+		index
+	}
+
+	/// Removes the table entry for `key`, returning the slot index it referred to.
+	fn unlink<Q: ?Sized + Hash + Eq>(&mut self, key: &Q) -> Option<usize>
+	where
+		K: Borrow<Q>,
+	{
+		let slots = &self.slots;
+		let entry = self
+			.table
+			.find_entry(
+				make_hash(&self.hash_builder, key),
+				|&index| matches!(slots.get(index), Some((k, _)) if k.borrow() == key),
+			)
+			.ok()?;
+		Some(entry.remove().0)
+	}
+
+	/// Inserts `value` for `key`.
+	///
+	/// If an equal key is present already, only its value is replaced and the previous value is returned.
+	pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+		if let Some(index) = self.find(&key) {
+			let (_, previous) = self.slots.get_mut(index)?;
+			Some(mem::replace(previous, value))
+		} else {
+			self.insert_unique(key, value);
+			None
+		}
+	}
+
+	#[must_use]
+	pub fn contains_key<Q: ?Sized + Hash + Eq>(&self, key: &Q) -> bool
+	where
+		K: Borrow<Q>,
+	{
+		self.find(key).is_some()
+	}
+
+	#[must_use]
+	pub fn get<Q: ?Sized + Hash + Eq>(&self, key: &Q) -> Option<&V>
+	where
+		K: Borrow<Q>,
+	{
+		self.find(key)
+			.and_then(|index| self.slots.get(index))
+			.map(|(_, value)| value)
+	}
+
+	pub fn get_mut<Q: ?Sized + Hash + Eq>(&mut self, key: &Q) -> Option<&mut V>
+	where
+		K: Borrow<Q>,
+	{
+		let index = self.find(key)?;
+		self.slots.get_mut(index).map(|(_, value)| value)
+	}
+
+	/// Removes and returns the entry for `key`, if any.
+	pub fn remove_entry<Q: ?Sized + Hash + Eq>(&mut self, key: &Q) -> Option<(K, V)>
+	where
+		K: Borrow<Q>,
+	{
+		let index = self.unlink(key)?;
+		self.slots.take(index)
+	}
+}
+
+impl<K, V> Default for PinnedHashMap<K, V> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<K, V, S> Drop for PinnedHashMap<K, V, S> {
+	fn drop(&mut self) {
+		self.slots.clear_in_place()
+	}
+}
+
+impl<K: Debug, V: Debug, S> Debug for PinnedHashMap<K, V, S> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_map().entries(self.iter()).finish()
+	}
+}
+
+impl<'a, K, V, S> IntoIterator for &'a PinnedHashMap<K, V, S> {
+	type Item = (&'a K, &'a V);
+	type IntoIter = Iter<'a, K, V>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+/// Iterates over the entries of a [`PinnedHashMap<K, V, S>`] as `(&K, &V)`.
+pub struct Iter<'a, K, V>(slots::Iter<'a, (K, V)>);
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+	type Item = (&'a K, &'a V);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.next().map(|(_, (key, value))| (key, value))
+	}
+}
+
+/// Iterates over the entries of a [`PinnedHashMap<K, V, S>`] as `&(K, V)`.
+pub struct Entries<'a, K, V>(slots::Iter<'a, (K, V)>);
+
+impl<'a, K, V> Iterator for Entries<'a, K, V> {
+	type Item = &'a (K, V);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.next().map(|(_, entry)| entry)
+	}
+}
+
+/// Iterates over the keys of a [`PinnedHashMap<K, V, S>`].
+pub struct Keys<'a, K, V>(slots::Iter<'a, (K, V)>);
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+	type Item = &'a K;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.next().map(|(_, (key, _))| key)
+	}
+}
+
+/// Iterates over the values of a [`PinnedHashMap<K, V, S>`].
+pub struct Values<'a, K, V>(slots::Iter<'a, (K, V)>);
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+	type Item = &'a V;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.next().map(|(_, (_, value))| value)
+	}
+}
+
+/// Iterates mutably over the values of a [`PinnedHashMap<K, V, S>`].
+pub struct ValuesMut<'a, K, V>(slots::IterMut<'a, (K, V)>);
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+	type Item = &'a mut V;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.next().map(|(_, (_, value))| value)
+	}
+}
+
+impl<K, V, S> Items<role::Keys> for PinnedHashMap<K, V, S> {
+	type Item = K;
+	type ItemsIter<'a>
+		= Keys<'a, K, V>
+	where
+		Self: 'a;
+
+	fn items(&self) -> Self::ItemsIter<'_> {
+		self.keys()
+	}
+}
+
+impl<K, V, S> ItemsDebug<role::Keys> for PinnedHashMap<K, V, S> {
+	/// Shows `len`.
+	fn debug_fields(&self, debug_struct: &mut DebugStruct) {
+		debug_struct.field("len", &self.len());
+	}
+}
+
+impl<K, V, S> Items<role::Values> for PinnedHashMap<K, V, S> {
+	type Item = V;
+	type ItemsIter<'a>
+		= Values<'a, K, V>
+	where
+		Self: 'a;
+
+	fn items(&self) -> Self::ItemsIter<'_> {
+		self.values()
+	}
+}
+
+impl<K, V, S> ItemsDebug<role::Values> for PinnedHashMap<K, V, S> {
+	/// Shows `len`.
+	fn debug_fields(&self, debug_struct: &mut DebugStruct) {
+		debug_struct.field("len", &self.len());
+	}
+}
+
+impl<K, V, S> ItemsMut<role::Values> for PinnedHashMap<K, V, S> {
+	type ItemsMutIter<'a>
+		= ValuesMut<'a, K, V>
+	where
+		Self: 'a;
+
+	fn items_mut(&mut self) -> Self::ItemsMutIter<'_> {
+		self.values_mut()
+	}
+}
+
+impl<K, V, S> Items<role::Entries> for PinnedHashMap<K, V, S> {
+	type Item = (K, V);
+	type ItemsIter<'a>
+		= Entries<'a, K, V>
+	where
+		Self: 'a;
+
+	fn items(&self) -> Self::ItemsIter<'_> {
+		Entries(self.slots.iter())
+	}
+}
+
+impl<K, V, S> ItemsDebug<role::Entries> for PinnedHashMap<K, V, S> {
+	/// Shows `len`.
+	fn debug_fields(&self, debug_struct: &mut DebugStruct) {
+		debug_struct.field("len", &self.len());
+	}
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> ItemsPin<role::Values, PinnedHashMap<K, V, S>> {
+	#[must_use]
+	pub fn pin(map: PinnedHashMap<K, V, S>) -> Self {
+		// SAFETY: The entries live in `Slots`, which never moves them, and the table only holds their indices.
+		// This API only drops values in place and never hands them out as `&mut V`.
+		unsafe { ItemsPin::new_unchecked(map) }
+	}
+
+	/// Inserts a new entry without moving any others and returns its value pinned.
+	///
+	/// If an entry with an equal key is present already, it is dropped in place first.
+	pub fn insert_pinned(&mut self, key: K, value: V) -> Pin<&mut V> {
+		self.remove(&key);
+		let index = self.collection.insert_unique(key, value);
+		let Some((_, value)) = self.collection.slots.get_mut(index) else {
+			unreachable!()
+		};
+		// SAFETY: The map is pinned, and the value stays in its slot until dropped in place.
+		unsafe { Pin::new_unchecked(value) }
+	}
+
+	#[must_use]
+	pub fn get_pinned<Q: ?Sized + Hash + Eq>(&self, key: &Q) -> Option<Pin<&V>>
+	where
+		K: Borrow<Q>,
+	{
+		#[cfg(feature = "check-pins")]
+		self.check_pins();
+		self.collection
+			.get(key)
+			// SAFETY: As above.
+			.map(|value| unsafe { Pin::new_unchecked(value) })
+	}
+
+	pub fn get_pinned_mut<Q: ?Sized + Hash + Eq>(&mut self, key: &Q) -> Option<Pin<&mut V>>
+	where
+		K: Borrow<Q>,
+	{
+		#[cfg(feature = "check-pins")]
+		self.check_pins();
+		self.collection
+			.get_mut(key)
+			// SAFETY: As above.
+			.map(|value| unsafe { Pin::new_unchecked(value) })
+	}
+
+	/// Drops the entry for `key` in place, if any.
+	pub fn remove<Q: ?Sized + Hash + Eq>(&mut self, key: &Q) -> bool
+	where
+		K: Borrow<Q>,
+	{
+		#[cfg(feature = "check-pins")]
+		self.check_pins();
+		let removed = match self.collection.unlink(key) {
+			Some(index) => self.collection.slots.remove_in_place(index),
+			None => false,
+		};
+		#[cfg(feature = "check-pins")]
+		self.record_pins();
+		removed
+	}
+}
@@ -0,0 +1,278 @@
+//! A hash set with [`role::Keys`] that keeps its values in place while growing.
+//!
+//! [`PinnedHashSet<T, S>`] stores its values in stable chunked slots and only moves slot indices around in its hash table,
+//! so it can be pinned via [`ItemsPin`] and still accept new values afterwards.
+//!
+//! Values can't be mutated through the set, as that could change their hashes.
+//! Before pinning (or if `T` is [`Unpin`]), values can be moved out again via `.take`.
+//!
+//! The added [`ItemsPin<role::Keys, PinnedHashSet<T, S>>`](`self`) methods are:
+//!
+//! - `.get_or_insert_pinned`, which returns the value equal to the given one, inserting it if necessary.
+//! - `.get_pinned`, which looks up a value by any borrowed form.
+//! - `.insert_pinned`, which adds a value iff no equal one is present, returning [`bool`].
+//! - `.remove_pinned`, which drops a value in place if present, returning [`bool`].
+
+use super::slots::{self, Slots};
+use crate::{role, Items, ItemsDebug, ItemsPin};
+use core::{
+	borrow::Borrow,
+	fmt::{self, Debug, DebugStruct, Formatter},
+	hash::{BuildHasher, Hash, Hasher},
+	pin::Pin,
+};
+use hashbrown::HashTable;
+use std::collections::hash_map::RandomState;
+
+/// A hash set whose values don't move once inserted.
+///
+/// Iteration order is unspecified.
+/// Values are dropped in place when the set is dropped.
+pub struct PinnedHashSet<T, S = RandomState> {
+	slots: Slots<T>,
+	/// Indices into `slots`.
+	table: HashTable<usize>,
+	hash_builder: S,
+}
+
+#[allow(clippy::manual_hash_one)] // `BuildHasher::hash_one` is too new for the MSRV.
+pub(super) fn make_hash<Q: ?Sized + Hash>(hash_builder: &impl BuildHasher, value: &Q) -> u64 {
+	let mut hasher = hash_builder.build_hasher();
+	value.hash(&mut hasher);
+	hasher.finish()
+}
+
+impl<T> PinnedHashSet<T> {
+	#[must_use]
+	pub fn new() -> Self {
+		Self::with_hasher(RandomState::new())
+	}
+}
+
+impl<T, S> PinnedHashSet<T, S> {
+	#[must_use]
+	pub const fn with_hasher(hash_builder: S) -> Self {
+		Self {
+			slots: Slots::new(),
+			table: HashTable::new(),
+			hash_builder,
+		}
+	}
+
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.slots.len()
+	}
+
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	#[must_use]
+	pub fn hasher(&self) -> &S {
+		&self.hash_builder
+	}
+
+	#[must_use]
+	pub fn iter(&self) -> Iter<'_, T> {
+		Iter(self.slots.iter())
+	}
+}
+
+impl<T: Hash + Eq, S: BuildHasher> PinnedHashSet<T, S> {
+	fn find<Q: ?Sized + Hash + Eq>(&self, value: &Q) -> Option<usize>
+	where
+		T: Borrow<Q>,
+	{
+		let slots = &self.slots;
+		self.table
+			.find(
+				make_hash(&self.hash_builder, value),
+				|&index| matches!(slots.get(index), Some(v) if v.borrow() == value),
+			)
+			.copied()
+	}
+
+	/// Finds the slot of `value`, inserting it first if no equal value is present.
+	///
+	/// Returns the slot index and whether `value` was inserted.
+	fn find_or_insert(&mut self, value: T) -> (usize, bool) {
+		let hash = make_hash(&self.hash_builder, &value);
+		let Self {
+			slots,
+			table,
+			hash_builder,
+		} = self;
+
+		if let Some(&index) = table.find(hash, |&index| slots.get(index) == Some(&value)) {
+			return (index, false);
+		}
+
+		let index = slots.insert(value);
+		table.insert_unique(hash, index, |&index| {
+			make_hash(hash_builder, slots.get(index).expect("dangling slot index"))
+		});
+		(index, true)
+	}
+
+	/// Removes the table entry for `value`, returning the slot index it referred to.
+	fn unlink<Q: ?Sized + Hash + Eq>(&mut self, value: &Q) -> Option<usize>
+	where
+		T: Borrow<Q>,
+	{
+		let slots = &self.slots;
+		let entry = self
+			.table
+			.find_entry(
+				make_hash(&self.hash_builder, value),
+				|&index| matches!(slots.get(index), Some(v) if v.borrow() == value),
+			)
+			.ok()?;
+		Some(entry.remove().0)
+	}
+
+	/// Adds `value` to the set iff no equal value is present yet.
+	///
+	/// Returns whether `value` was added.
+	/// Otherwise, it is dropped and the existing value is kept.
+	pub fn insert(&mut self, value: T) -> bool {
+		self.find_or_insert(value).1
+	}
+
+	#[must_use]
+	pub fn contains<Q: ?Sized + Hash + Eq>(&self, value: &Q) -> bool
+	where
+		T: Borrow<Q>,
+	{
+		self.find(value).is_some()
+	}
+
+	#[must_use]
+	pub fn get<Q: ?Sized + Hash + Eq>(&self, value: &Q) -> Option<&T>
+	where
+		T: Borrow<Q>,
+	{
+		self.find(value).and_then(|index| self.slots.get(index))
+	}
+
+	/// Removes and returns the value equal to `value`, if any.
+	pub fn take<Q: ?Sized + Hash + Eq>(&mut self, value: &Q) -> Option<T>
+	where
+		T: Borrow<Q>,
+	{
+		let index = self.unlink(value)?;
+		self.slots.take(index)
+	}
+}
+
+impl<T> Default for PinnedHashSet<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T, S> Drop for PinnedHashSet<T, S> {
+	fn drop(&mut self) {
+		self.slots.clear_in_place()
+	}
+}
+
+impl<T: Debug, S> Debug for PinnedHashSet<T, S> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_set().entries(self.iter()).finish()
+	}
+}
+
+impl<'a, T, S> IntoIterator for &'a PinnedHashSet<T, S> {
+	type Item = &'a T;
+	type IntoIter = Iter<'a, T>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+/// Iterates over the values of a [`PinnedHashSet<T, S>`].
+pub struct Iter<'a, T>(slots::Iter<'a, T>);
+
+impl<'a, T> Iterator for Iter<'a, T> {
+	type Item = &'a T;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.next().map(|(_, value)| value)
+	}
+}
+
+impl<T, S> Items<role::Keys> for PinnedHashSet<T, S> {
+	type Item = T;
+	type ItemsIter<'a>
+		= Iter<'a, T>
+	where
+		Self: 'a;
+
+	fn items(&self) -> Self::ItemsIter<'_> {
+		self.iter()
+	}
+}
+
+impl<T, S> ItemsDebug<role::Keys> for PinnedHashSet<T, S> {
+	/// Shows `len`.
+	fn debug_fields(&self, debug_struct: &mut DebugStruct) {
+		debug_struct.field("len", &self.len());
+	}
+}
+
+impl<T: Hash + Eq, S: BuildHasher> ItemsPin<role::Keys, PinnedHashSet<T, S>> {
+	#[must_use]
+	pub fn pin(set: PinnedHashSet<T, S>) -> Self {
+		// SAFETY: The values live in `Slots`, which never moves them, and this API only drops them in place.
+		unsafe { ItemsPin::new_unchecked(set) }
+	}
+
+	/// Adds `value` iff no equal value is present yet, without moving any others.
+	///
+	/// Returns whether `value` was added.
+	pub fn insert_pinned(&mut self, value: T) -> bool {
+		self.collection.insert(value)
+	}
+
+	/// Returns the value equal to `value`, inserting `value` first if there is none.
+	pub fn get_or_insert_pinned(&mut self, value: T) -> Pin<&T> {
+		let (index, _) = self.collection.find_or_insert(value);
+		let Some(value) = self.collection.slots.get(index) else {
+			unreachable!()
+		};
+		// SAFETY: The set is pinned, and the value stays in its slot until dropped in place.
+		unsafe { Pin::new_unchecked(value) }
+	}
+
+	#[must_use]
+	pub fn get_pinned<Q: ?Sized + Hash + Eq>(&self, value: &Q) -> Option<Pin<&T>>
+	where
+		T: Borrow<Q>,
+	{
+		#[cfg(feature = "check-pins")]
+		self.check_pins();
+		self.collection
+			.get(value)
+			// SAFETY: As above.
+			.map(|value| unsafe { Pin::new_unchecked(value) })
+	}
+
+	/// Drops the value equal to `value` in place, if any.
+	pub fn remove_pinned<Q: ?Sized + Hash + Eq>(&mut self, value: &Q) -> bool
+	where
+		T: Borrow<Q>,
+	{
+		#[cfg(feature = "check-pins")]
+		self.check_pins();
+		let removed = match self.collection.unlink(value) {
+			Some(index) => self.collection.slots.remove_in_place(index),
+			None => false,
+		};
+		#[cfg(feature = "check-pins")]
+		self.record_pins();
+		removed
+	}
+}
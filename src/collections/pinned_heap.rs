@@ -0,0 +1,240 @@
+//! A priority queue with [`role::Items`] whose items stay in place while the queue is reordered.
+//!
+//! [`PinnedHeap<K, T>`] stores its items in stable chunked slots and only sifts `(key, slot index)` pairs around in a separate [`BinaryHeap`],
+//! so it can be pinned via [`ItemsPin`] and still accept new items afterwards.
+//! Like [`BinaryHeap`], it is a max-heap. Use [`Reverse`](`core::cmp::Reverse`) keys to pop the smallest key first.
+//!
+//! The added [`ItemsPin<role::Items, PinnedHeap<K, T>>`](`self`) methods are:
+//!
+//! - `.peek_pinned_mut`, which returns the greatest key and its item.
+//! - `.pop_pinned`, which runs a closure on the item with the greatest key and then drops it in place.
+//! - `.push_pinned`, which always succeeds.
+
+use super::slots::{self, Slots};
+use crate::{role, Items, ItemsDebug, ItemsMut, ItemsPin};
+use alloc::collections::BinaryHeap;
+use core::{
+	cmp::Ordering,
+	fmt::{self, Debug, DebugStruct, Formatter},
+	pin::Pin,
+};
+
+/// A key and the slot of its item, ordered by key only.
+struct Entry<K> {
+	key: K,
+	index: usize,
+}
+
+impl<K: Ord> PartialEq for Entry<K> {
+	fn eq(&self, other: &Self) -> bool {
+		self.key == other.key
+	}
+}
+
+impl<K: Ord> Eq for Entry<K> {}
+
+impl<K: Ord> PartialOrd for Entry<K> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<K: Ord> Ord for Entry<K> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.key.cmp(&other.key)
+	}
+}
+
+/// A max-heap of items ordered by separate keys, whose items don't move once inserted.
+///
+/// The order of items with equal keys is unspecified.
+/// Items are dropped in place when the heap is dropped.
+pub struct PinnedHeap<K, T> {
+	slots: Slots<T>,
+	heap: BinaryHeap<Entry<K>>,
+}
+
+impl<K: Ord, T> PinnedHeap<K, T> {
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			slots: Slots::new(),
+			heap: BinaryHeap::new(),
+		}
+	}
+
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.slots.len()
+	}
+
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	pub fn push(&mut self, key: K, value: T) {
+		let index = self.slots.insert(value);
+		self.heap.push(Entry { key, index });
+	}
+
+	/// Returns the greatest key and its item.
+	#[must_use]
+	pub fn peek(&self) -> Option<(&K, &T)> {
+		let entry = self.heap.peek()?;
+		Some((&entry.key, self.slots.get(entry.index)?))
+	}
+
+	/// Removes and returns the greatest key and its item.
+	pub fn pop(&mut self) -> Option<(K, T)> {
+		let Entry { key, index } = self.heap.pop()?;
+		Some((key, self.slots.take(index)?))
+	}
+
+	/// Iterates over the items in unspecified order.
+	#[must_use]
+	pub fn iter(&self) -> Iter<'_, T> {
+		Iter(self.slots.iter())
+	}
+
+	/// Iterates mutably over the items in unspecified order.
+	#[must_use]
+	pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+		IterMut(self.slots.iter_mut())
+	}
+}
+
+impl<K: Ord, T> Default for PinnedHeap<K, T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<K, T> Drop for PinnedHeap<K, T> {
+	fn drop(&mut self) {
+		self.slots.clear_in_place()
+	}
+}
+
+/// Formats as a list of `(key, item)` pairs in unspecified order.
+impl<K: Debug, T: Debug> Debug for PinnedHeap<K, T> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_list()
+			.entries(
+				self.heap
+					.iter()
+					.filter_map(|entry| Some((&entry.key, self.slots.get(entry.index)?))),
+			)
+			.finish()
+	}
+}
+
+impl<'a, K: Ord, T> IntoIterator for &'a PinnedHeap<K, T> {
+	type Item = &'a T;
+	type IntoIter = Iter<'a, T>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+impl<'a, K: Ord, T> IntoIterator for &'a mut PinnedHeap<K, T> {
+	type Item = &'a mut T;
+	type IntoIter = IterMut<'a, T>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter_mut()
+	}
+}
+
+/// Iterates over the items of a [`PinnedHeap<K, T>`] in unspecified order.
+pub struct Iter<'a, T>(slots::Iter<'a, T>);
+
+impl<'a, T> Iterator for Iter<'a, T> {
+	type Item = &'a T;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.next().map(|(_, item)| item)
+	}
+}
+
+/// Iterates mutably over the items of a [`PinnedHeap<K, T>`] in unspecified order.
+pub struct IterMut<'a, T>(slots::IterMut<'a, T>);
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+	type Item = &'a mut T;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.next().map(|(_, item)| item)
+	}
+}
+
+impl<K, T> Items<role::Items> for PinnedHeap<K, T> {
+	type Item = T;
+	type ItemsIter<'a>
+		= Iter<'a, T>
+	where
+		Self: 'a;
+
+	fn items(&self) -> Self::ItemsIter<'_> {
+		Iter(self.slots.iter())
+	}
+}
+
+impl<K, T> ItemsDebug<role::Items> for PinnedHeap<K, T> {
+	/// Shows `len`.
+	fn debug_fields(&self, debug_struct: &mut DebugStruct) {
+		debug_struct.field("len", &self.slots.len());
+	}
+}
+
+impl<K, T> ItemsMut<role::Items> for PinnedHeap<K, T> {
+	type ItemsMutIter<'a>
+		= IterMut<'a, T>
+	where
+		Self: 'a;
+
+	fn items_mut(&mut self) -> Self::ItemsMutIter<'_> {
+		IterMut(self.slots.iter_mut())
+	}
+}
+
+impl<K: Ord, T> ItemsPin<role::Items, PinnedHeap<K, T>> {
+	#[must_use]
+	pub fn pin(heap: PinnedHeap<K, T>) -> Self {
+		// SAFETY: The items live in `Slots`, which never moves them. Sifting only moves the `Entry`s.
+		// This API only drops items in place.
+		unsafe { ItemsPin::new_unchecked(heap) }
+	}
+
+	/// Inserts `value` with `key`, without moving any other items.
+	pub fn push_pinned(&mut self, key: K, value: T) {
+		self.collection.push(key, value)
+	}
+
+	/// Returns the greatest key and its item.
+	pub fn peek_pinned_mut(&mut self) -> Option<(&K, Pin<&mut T>)> {
+		let heap = &mut self.collection;
+		let entry = heap.heap.peek()?;
+		let item = heap.slots.get_mut(entry.index)?;
+		// SAFETY: The heap is pinned, and the item stays in its slot until dropped in place.
+		Some((&entry.key, unsafe { Pin::new_unchecked(item) }))
+	}
+
+	/// Runs `f` on the item with the greatest key, then drops that item in place.
+	///
+	/// Returns the key and `f`'s result, or [`None`] iff the heap is empty.
+	///
+	/// If `f` panics, the item stays in the heap.
+	pub fn pop_pinned<R>(&mut self, f: impl FnOnce(Pin<&mut T>) -> R) -> Option<(K, R)> {
+		let (_, item) = self.peek_pinned_mut()?;
+		let result = f(item);
+
+		let heap = &mut self.collection;
+		let Entry { key, index } = heap.heap.pop()?;
+		heap.slots.remove_in_place(index);
+		#[cfg(feature = "check-pins")]
+		self.record_pins();
+		Some((key, result))
+	}
+}
@@ -0,0 +1,227 @@
+//! A fixed-capacity ring buffer with [`role::Items`].
+//!
+//! [`PinnedRing<T>`] allocates its buffer once and never reallocates it,
+//! so it can be pinned via [`ItemsPin`] like a [`Vec<T>`](`crate::std_impls::vec`).
+//! Items are iterated in logical order, from oldest to newest.
+//!
+//! The added [`ItemsPin<role::Items, PinnedRing<T>>`](`self`) methods are:
+//!
+//! - `.pop_front_pinned`, which drops the oldest value in place if possible, returning [`bool`].
+//! - `.push_overwrite`, which drops the oldest value in place if the buffer is full
+//!   and then constructs the new value in its slot.
+//! - `.push_pinned`, which appends a value only if the buffer isn't full.
+
+use crate::{role, Items, ItemsDebug, ItemsMut, ItemsPin};
+use alloc::boxed::Box;
+use core::{
+	fmt::{self, Debug, DebugStruct, Formatter},
+	iter::Chain,
+	mem::MaybeUninit,
+	ptr,
+	slice::{self, Iter, IterMut},
+};
+
+/// A ring buffer with a fixed capacity.
+///
+/// Items are dropped in place when the ring is dropped.
+pub struct PinnedRing<T> {
+	buffer: Box<[MaybeUninit<T>]>,
+	/// Index of the oldest item.
+	head: usize,
+	len: usize,
+}
+
+impl<T> PinnedRing<T> {
+	#[must_use]
+	pub fn with_capacity(capacity: usize) -> Self {
+		Self {
+			buffer: (0..capacity).map(|_| MaybeUninit::uninit()).collect(),
+			head: 0,
+			len: 0,
+		}
+	}
+
+	#[must_use]
+	pub fn capacity(&self) -> usize {
+		self.buffer.len()
+	}
+
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	#[must_use]
+	pub fn is_full(&self) -> bool {
+		self.len == self.capacity()
+	}
+
+	/// The index of the slot `offset` places after the oldest item.
+	fn slot(&self, offset: usize) -> usize {
+		let index = self.head + offset;
+		if index < self.capacity() {
+			index
+		} else {
+			index - self.capacity()
+		}
+	}
+
+	/// Returns the occupied slots as `(older, newer)` slices.
+	#[must_use]
+	pub fn as_slices(&self) -> (&[T], &[T]) {
+		let (first, second) = self.ranges();
+		unsafe {
+			let buffer = self.buffer.as_ptr().cast::<T>();
+			(
+				slice::from_raw_parts(buffer.add(first.0), first.1),
+				slice::from_raw_parts(buffer.add(second.0), second.1),
+			)
+		}
+	}
+
+	/// Returns the occupied slots as `(older, newer)` slices.
+	#[must_use]
+	pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+		let (first, second) = self.ranges();
+		unsafe {
+			let buffer = self.buffer.as_mut_ptr().cast::<T>();
+			(
+				slice::from_raw_parts_mut(buffer.add(first.0), first.1),
+				slice::from_raw_parts_mut(buffer.add(second.0), second.1),
+			)
+		}
+	}
+
+	/// The occupied slots as `((start, len), (start, len))`, in logical order.
+	fn ranges(&self) -> ((usize, usize), (usize, usize)) {
+		let first_len = self.len.min(self.capacity() - self.head);
+		((self.head, first_len), (0, self.len - first_len))
+	}
+
+	/// Appends `value` as newest item.
+	///
+	/// # Errors
+	///
+	/// Iff the buffer is full.
+	pub fn push_back(&mut self, value: T) -> Result<(), T> {
+		if self.is_full() {
+			return Err(value);
+		}
+
+		let slot = self.slot(self.len);
+		self.buffer[slot] = MaybeUninit::new(value);
+		self.len += 1;
+		Ok(())
+	}
+
+	/// Removes and returns the oldest item.
+	pub fn pop_front(&mut self) -> Option<T> {
+		if self.is_empty() {
+			return None;
+		}
+
+		let value = unsafe { self.buffer[self.head].as_ptr().read() };
+		self.head = self.slot(1);
+		self.len -= 1;
+		Some(value)
+	}
+
+	/// Drops the oldest item in place, without moving any others.
+	fn drop_front_in_place(&mut self) -> bool {
+		if self.is_empty() {
+			return false;
+		}
+
+		let oldest = self.buffer[self.head].as_mut_ptr();
+		// Advance first, so that a panicking destructor leaks instead of double-dropping.
+		self.head = self.slot(1);
+		self.len -= 1;
+		unsafe { ptr::drop_in_place(oldest) };
+		true
+	}
+}
+
+impl<T> Drop for PinnedRing<T> {
+	fn drop(&mut self) {
+		while self.drop_front_in_place() {}
+	}
+}
+
+impl<T: Debug> Debug for PinnedRing<T> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_list().entries(self.items()).finish()
+	}
+}
+
+impl<T> Items<role::Items> for PinnedRing<T> {
+	type Item = T;
+	type ItemsIter<'a>
+		= Chain<Iter<'a, T>, Iter<'a, T>>
+	where
+		Self: 'a;
+
+	fn items(&self) -> Self::ItemsIter<'_> {
+		let (older, newer) = self.as_slices();
+		older.iter().chain(newer)
+	}
+}
+
+impl<T> ItemsDebug<role::Items> for PinnedRing<T> {
+	/// Shows `len` and `capacity`.
+	fn debug_fields(&self, debug_struct: &mut DebugStruct) {
+		debug_struct
+			.field("len", &self.len())
+			.field("capacity", &self.capacity());
+	}
+}
+
+impl<T> ItemsMut<role::Items> for PinnedRing<T> {
+	type ItemsMutIter<'a>
+		= Chain<IterMut<'a, T>, IterMut<'a, T>>
+	where
+		Self: 'a;
+
+	fn items_mut(&mut self) -> Self::ItemsMutIter<'_> {
+		let (older, newer) = self.as_mut_slices();
+		older.iter_mut().chain(newer)
+	}
+}
+
+impl<T> ItemsPin<role::Items, PinnedRing<T>> {
+	#[must_use]
+	pub fn pin(ring: PinnedRing<T>) -> Self {
+		unsafe { ItemsPin::new_unchecked(ring) }
+	}
+
+	/// # Errors
+	///
+	/// Iff the buffer is full.
+	pub fn push_pinned(&mut self, value: T) -> Result<(), T> {
+		self.collection.push_back(value)
+	}
+
+	/// Appends `value` as newest item.
+	///
+	/// If the buffer is full, the oldest item is dropped in place first and `value` takes over its slot.
+	/// (If the capacity is zero, `value` is dropped immediately instead.)
+	pub fn push_overwrite(&mut self, value: T) {
+		if self.collection.is_full() {
+			self.pop_front_pinned();
+		}
+		self.collection.push_back(value).ok();
+	}
+
+	pub fn pop_front_pinned(&mut self) -> bool {
+		#[cfg(feature = "check-pins")]
+		self.check_pins();
+		let popped = self.collection.drop_front_in_place();
+		#[cfg(feature = "check-pins")]
+		self.record_pins();
+		popped
+	}
+}
@@ -0,0 +1,106 @@
+//! A range of virtual memory that is reserved up front and committed page-wise as it's used (Linux only).
+
+use core::{
+	convert::TryFrom,
+	ptr::{self, NonNull},
+};
+
+/// An anonymous private mapping that starts out inaccessible and without backing memory.
+///
+/// Unmapped on drop. Any values inside must be dropped beforehand.
+pub(crate) struct Reservation {
+	start: NonNull<u8>,
+	/// In bytes, a multiple of `page_size`.
+	reserved: usize,
+	/// In bytes, a multiple of `page_size`. The accessible prefix of the reservation.
+	committed: usize,
+	page_size: usize,
+}
+
+/// The mapping is only accessed through `&mut self` or the reservation's owner.
+unsafe impl Send for Reservation {}
+unsafe impl Sync for Reservation {}
+
+impl Reservation {
+	/// Reserves at least `bytes` bytes of address space without committing any of it.
+	///
+	/// Returns [`None`] if `bytes` is zero or the reservation fails.
+	pub(crate) fn new(bytes: usize) -> Option<Self> {
+		let page_size = usize::try_from(unsafe { libc::sysconf(libc::_SC_PAGESIZE) }).ok()?;
+		let reserved = round_up(bytes, page_size)?;
+		if reserved == 0 {
+			return None;
+		}
+
+		let start = unsafe {
+			libc::mmap(
+				ptr::null_mut(),
+				reserved,
+				libc::PROT_NONE,
+				libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_NORESERVE,
+				-1,
+				0,
+			)
+		};
+		if start == libc::MAP_FAILED {
+			return None;
+		}
+
+		Some(Self {
+			start: NonNull::new(start.cast())?,
+			reserved,
+			committed: 0,
+			page_size,
+		})
+	}
+
+	/// Page-aligned.
+	pub(crate) fn as_ptr(&self) -> *mut u8 {
+		self.start.as_ptr()
+	}
+
+	pub(crate) fn page_size(&self) -> usize {
+		self.page_size
+	}
+
+	/// Makes at least the first `bytes` bytes accessible, at least doubling the committed range where possible.
+	///
+	/// Returns `false` iff `bytes` exceeds the reservation or the memory couldn't be committed.
+	/// Already committed memory is never moved or decommitted.
+	pub(crate) fn commit(&mut self, bytes: usize) -> bool {
+		if bytes <= self.committed {
+			return true;
+		} else if bytes > self.reserved {
+			return false;
+		}
+
+		let Some(committed) = round_up(bytes.max(self.committed.saturating_mul(2)), self.page_size)
+		else {
+			return false;
+		};
+		let committed = committed.min(self.reserved);
+		let result = unsafe {
+			libc::mprotect(
+				self.as_ptr().add(self.committed).cast(),
+				committed - self.committed,
+				libc::PROT_READ | libc::PROT_WRITE,
+			)
+		};
+		if result == 0 {
+			self.committed = committed;
+			true
+		} else {
+			false
+		}
+	}
+}
+
+impl Drop for Reservation {
+	fn drop(&mut self) {
+		unsafe { libc::munmap(self.as_ptr().cast(), self.reserved) };
+	}
+}
+
+fn round_up(bytes: usize, page_size: usize) -> Option<usize> {
+	Some(bytes.checked_add(page_size - 1)? / page_size * page_size)
+}
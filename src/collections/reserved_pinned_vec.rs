@@ -0,0 +1,434 @@
+//! A vector with [`role::Items`] that never reallocates, backed by reserved virtual memory where possible.
+//!
+//! On Linux, [`ReservedPinnedVec<T>`] reserves address space for its maximum length up front (via `mmap` without committing it)
+//! and commits pages as it grows, so its items are stored contiguously and never relocated.
+//! Elsewhere, or if the reservation fails, it falls back to chunks of doubling capacity that are never reallocated
+//! (like [`PinArena<T>`](`super::PinArena`)).
+//! Either way, it can be pinned via [`ItemsPin`] and still accept new items up to its maximum length.
+//!
+//! The added [`ItemsPin<role::Items, ReservedPinnedVec<T>>`](`self`) methods are:
+//!
+//! - `.get_pinned` and `.get_pinned_mut`, which return the item at an index, if there is one.
+//! - `.pop_pinned`, which drops the last item in place if possible, returning [`bool`].
+//! - `.push_pinned`, which appends an item without moving any others.
+//! - `.truncate_pinned`, which drops the items from an index onwards in place, in index order.
+
+#[cfg(target_os = "linux")]
+use super::reservation::Reservation;
+use crate::{role, Items, ItemsDebug, ItemsMut, ItemsPin};
+use alloc::vec::Vec;
+use core::{
+	fmt::{self, Debug, DebugStruct, Formatter},
+	iter::Flatten,
+	pin::Pin,
+	slice,
+};
+#[cfg(target_os = "linux")]
+use core::{marker::PhantomData, mem, ptr};
+
+const FIRST_CHUNK_CAPACITY: usize = 32;
+
+/// A vector of up to `max_len` items that are never moved by pushing more.
+///
+/// Items are dropped in place when the vector is dropped, in index order.
+pub struct ReservedPinnedVec<T> {
+	max_len: usize,
+	storage: Storage<T>,
+}
+
+enum Storage<T> {
+	#[cfg(target_os = "linux")]
+	Reserved {
+		memory: Reservation,
+		len: usize,
+		_phantom: PhantomData<T>,
+	},
+	/// Only pushed to within capacity, except for the outer [`Vec`]. Only the last chunk has spare capacity.
+	Segmented(Vec<Vec<T>>),
+}
+
+impl<T> ReservedPinnedVec<T> {
+	/// Creates an empty vector that can hold up to `max_len` items,
+	/// reserving (but not committing) the address space for all of them if possible.
+	///
+	/// This falls back to [`ReservedPinnedVec::with_segments`] on platforms other than Linux,
+	/// for zero-sized `T` and if the reservation fails.
+	#[must_use]
+	pub fn with_reservation(max_len: usize) -> Self {
+		#[cfg(target_os = "linux")]
+		if mem::size_of::<T>() != 0 {
+			if let Some(memory) = max_len
+				.checked_mul(mem::size_of::<T>())
+				.and_then(Reservation::new)
+			{
+				if mem::align_of::<T>() <= memory.page_size() {
+					return Self {
+						max_len,
+						storage: Storage::Reserved {
+							memory,
+							len: 0,
+							_phantom: PhantomData,
+						},
+					};
+				}
+			}
+		}
+
+		Self::with_segments(max_len)
+	}
+
+	/// Creates an empty vector that can hold up to `max_len` items in chunks of doubling capacity, without reserving address space.
+	#[must_use]
+	pub const fn with_segments(max_len: usize) -> Self {
+		Self {
+			max_len,
+			storage: Storage::Segmented(Vec::new()),
+		}
+	}
+
+	/// Whether the items are stored in reserved virtual memory, rather than in chunks.
+	#[must_use]
+	pub fn is_reserved(&self) -> bool {
+		match self.storage {
+			#[cfg(target_os = "linux")]
+			Storage::Reserved { .. } => true,
+			Storage::Segmented(_) => false,
+		}
+	}
+
+	#[must_use]
+	pub fn max_len(&self) -> usize {
+		self.max_len
+	}
+
+	#[must_use]
+	pub fn len(&self) -> usize {
+		match &self.storage {
+			#[cfg(target_os = "linux")]
+			Storage::Reserved { len, .. } => *len,
+			Storage::Segmented(chunks) => chunks.iter().map(Vec::len).sum(),
+		}
+	}
+
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Appends `value` without moving any existing items.
+	///
+	/// # Errors
+	///
+	/// Iff the vector already holds `max_len` items or memory for `value` couldn't be committed or allocated.
+	pub fn push(&mut self, value: T) -> Result<(), T> {
+		let len = self.len();
+		if len >= self.max_len {
+			return Err(value);
+		}
+
+		match &mut self.storage {
+			#[cfg(target_os = "linux")]
+			Storage::Reserved { memory, len, .. } => {
+				if !memory.commit((*len + 1) * mem::size_of::<T>()) {
+					return Err(value);
+				}
+				unsafe { memory.as_ptr().cast::<T>().add(*len).write(value) };
+				*len += 1;
+			}
+			Storage::Segmented(chunks) => {
+				let new_capacity = match chunks.last() {
+					Some(chunk) if chunk.len() < chunk.capacity() => None,
+					Some(chunk) => Some(chunk.capacity().saturating_mul(2)),
+					None => Some(FIRST_CHUNK_CAPACITY),
+				};
+				if let Some(capacity) = new_capacity {
+					// Never more than the remaining items need.
+					let capacity = capacity.min(self.max_len - len);
+					// Allocation failure is reported like a full vector, rather than aborting.
+					let mut chunk = Vec::new();
+					if chunk.try_reserve_exact(capacity).is_err()
+						|| chunks.try_reserve_exact(1).is_err()
+					{
+						return Err(value);
+					}
+					chunks.push(chunk);
+				}
+
+				let Some(chunk) = chunks.last_mut() else {
+					unreachable!()
+				};
+				// Within capacity, so this doesn't reallocate.
+				chunk.push(value);
+			}
+		}
+		Ok(())
+	}
+
+	/// Removes and returns the last item, if any.
+	pub fn pop(&mut self) -> Option<T> {
+		match &mut self.storage {
+			#[cfg(target_os = "linux")]
+			Storage::Reserved { memory, len, .. } => {
+				*len = len.checked_sub(1)?;
+				Some(unsafe { memory.as_ptr().cast::<T>().add(*len).read() })
+			}
+			Storage::Segmented(chunks) => {
+				let value = chunks.last_mut()?.pop()?;
+				if let Some(chunk) = chunks.last() {
+					if chunk.is_empty() {
+						chunks.pop();
+					}
+				}
+				Some(value)
+			}
+		}
+	}
+
+	/// Drops the items from index `len` onwards in place, in index order.
+	///
+	/// Committed memory and chunks that are still partially used are kept for reuse.
+	pub fn truncate(&mut self, len: usize) {
+		match &mut self.storage {
+			#[cfg(target_os = "linux")]
+			Storage::Reserved {
+				memory,
+				len: old_len,
+				..
+			} => {
+				if len < *old_len {
+					let tail = ptr::slice_from_raw_parts_mut(
+						unsafe { memory.as_ptr().cast::<T>().add(len) },
+						*old_len - len,
+					);
+					// Shortened first, in case a `drop` panics.
+					*old_len = len;
+					unsafe { ptr::drop_in_place(tail) };
+				}
+			}
+			Storage::Segmented(chunks) => {
+				let mut start = 0;
+				let mut keep = 0;
+				for chunk in chunks.iter_mut() {
+					if start >= len {
+						break;
+					}
+					chunk.truncate(len - start);
+					start += chunk.len();
+					keep += 1;
+				}
+				// Drops the remaining chunks in order, each of which drops its items in order.
+				chunks.truncate(keep);
+			}
+		}
+	}
+
+	#[must_use]
+	pub fn get(&self, mut index: usize) -> Option<&T> {
+		match &self.storage {
+			#[cfg(target_os = "linux")]
+			Storage::Reserved { memory, len, .. } => {
+				(index < *len).then(|| unsafe { &*memory.as_ptr().cast::<T>().add(index) })
+			}
+			Storage::Segmented(chunks) => chunks.iter().find_map(|chunk| {
+				if index < chunk.len() {
+					chunk.get(index)
+				} else {
+					index -= chunk.len();
+					None
+				}
+			}),
+		}
+	}
+
+	pub fn get_mut(&mut self, mut index: usize) -> Option<&mut T> {
+		match &mut self.storage {
+			#[cfg(target_os = "linux")]
+			Storage::Reserved { memory, len, .. } => {
+				(index < *len).then(|| unsafe { &mut *memory.as_ptr().cast::<T>().add(index) })
+			}
+			Storage::Segmented(chunks) => chunks.iter_mut().find_map(|chunk| {
+				if index < chunk.len() {
+					chunk.get_mut(index)
+				} else {
+					index -= chunk.len();
+					None
+				}
+			}),
+		}
+	}
+
+	#[must_use]
+	pub fn iter(&self) -> Iter<'_, T> {
+		Iter(match &self.storage {
+			#[cfg(target_os = "linux")]
+			Storage::Reserved { memory, len, .. } => IterInner::Reserved(
+				unsafe { slice::from_raw_parts(memory.as_ptr().cast::<T>(), *len) }.iter(),
+			),
+			Storage::Segmented(chunks) => IterInner::Segmented(chunks.iter().flatten()),
+		})
+	}
+
+	#[must_use]
+	pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+		IterMut(match &mut self.storage {
+			#[cfg(target_os = "linux")]
+			Storage::Reserved { memory, len, .. } => IterMutInner::Reserved(
+				unsafe { slice::from_raw_parts_mut(memory.as_ptr().cast::<T>(), *len) }.iter_mut(),
+			),
+			Storage::Segmented(chunks) => IterMutInner::Segmented(chunks.iter_mut().flatten()),
+		})
+	}
+}
+
+impl<T> Drop for ReservedPinnedVec<T> {
+	fn drop(&mut self) {
+		self.truncate(0)
+	}
+}
+
+impl<T: Debug> Debug for ReservedPinnedVec<T> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_list().entries(self.iter()).finish()
+	}
+}
+
+impl<'a, T> IntoIterator for &'a ReservedPinnedVec<T> {
+	type Item = &'a T;
+	type IntoIter = Iter<'a, T>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+impl<'a, T> IntoIterator for &'a mut ReservedPinnedVec<T> {
+	type Item = &'a mut T;
+	type IntoIter = IterMut<'a, T>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter_mut()
+	}
+}
+
+/// Iterates over the items of a [`ReservedPinnedVec<T>`] in index order.
+pub struct Iter<'a, T>(IterInner<'a, T>);
+
+enum IterInner<'a, T> {
+	#[cfg(target_os = "linux")]
+	Reserved(slice::Iter<'a, T>),
+	Segmented(Flatten<slice::Iter<'a, Vec<T>>>),
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+	type Item = &'a T;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		match &mut self.0 {
+			#[cfg(target_os = "linux")]
+			IterInner::Reserved(iter) => iter.next(),
+			IterInner::Segmented(iter) => iter.next(),
+		}
+	}
+}
+
+/// Iterates mutably over the items of a [`ReservedPinnedVec<T>`] in index order.
+pub struct IterMut<'a, T>(IterMutInner<'a, T>);
+
+enum IterMutInner<'a, T> {
+	#[cfg(target_os = "linux")]
+	Reserved(slice::IterMut<'a, T>),
+	Segmented(Flatten<slice::IterMut<'a, Vec<T>>>),
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+	type Item = &'a mut T;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		match &mut self.0 {
+			#[cfg(target_os = "linux")]
+			IterMutInner::Reserved(iter) => iter.next(),
+			IterMutInner::Segmented(iter) => iter.next(),
+		}
+	}
+}
+
+impl<T> Items<role::Items> for ReservedPinnedVec<T> {
+	type Item = T;
+	type ItemsIter<'a>
+		= Iter<'a, T>
+	where
+		Self: 'a;
+
+	fn items(&self) -> Self::ItemsIter<'_> {
+		self.iter()
+	}
+}
+
+impl<T> ItemsDebug<role::Items> for ReservedPinnedVec<T> {
+	/// Shows `len`, `max_len` and whether the items are stored in reserved memory.
+	fn debug_fields(&self, debug_struct: &mut DebugStruct) {
+		debug_struct
+			.field("len", &self.len())
+			.field("max_len", &self.max_len)
+			.field("reserved", &self.is_reserved());
+	}
+}
+
+impl<T> ItemsMut<role::Items> for ReservedPinnedVec<T> {
+	type ItemsMutIter<'a>
+		= IterMut<'a, T>
+	where
+		Self: 'a;
+
+	fn items_mut(&mut self) -> Self::ItemsMutIter<'_> {
+		self.iter_mut()
+	}
+}
+
+impl<T> ItemsPin<role::Items, ReservedPinnedVec<T>> {
+	#[must_use]
+	pub fn pin(vec: ReservedPinnedVec<T>) -> Self {
+		unsafe { ItemsPin::new_unchecked(vec) }
+	}
+
+	#[must_use]
+	pub fn get_pinned(&self, index: usize) -> Option<Pin<&T>> {
+		#[cfg(feature = "check-pins")]
+		self.check_pins();
+		self.collection
+			.get(index)
+			.map(|item| unsafe { Pin::new_unchecked(item) })
+	}
+
+	pub fn get_pinned_mut(&mut self, index: usize) -> Option<Pin<&mut T>> {
+		#[cfg(feature = "check-pins")]
+		self.check_pins();
+		self.collection
+			.get_mut(index)
+			.map(|item| unsafe { Pin::new_unchecked(item) })
+	}
+
+	/// Appends `value` without moving any existing items.
+	///
+	/// # Errors
+	///
+	/// Iff the vector already holds `max_len` items or memory for `value` couldn't be committed or allocated.
+	pub fn push_pinned(&mut self, value: T) -> Result<(), T> {
+		self.collection.push(value)
+	}
+
+	pub fn pop_pinned(&mut self) -> bool {
+		!self.collection.is_empty() && {
+			self.truncate_pinned(self.collection.len() - 1);
+			true
+		}
+	}
+
+	pub fn truncate_pinned(&mut self, len: usize) {
+		#[cfg(feature = "check-pins")]
+		self.check_pins();
+		self.collection.truncate(len);
+		#[cfg(feature = "check-pins")]
+		self.record_pins();
+	}
+}
@@ -0,0 +1,226 @@
+//! Stable chunked slot storage that [`HoleyVec<T>`](`super::HoleyVec`) and the hash-based collections are built on.
+//!
+//! Slots live in chunks of doubling size that are never reallocated,
+//! so values stay in place from insertion until removal regardless of how many others are added.
+//! Vacated slots are reused by later insertions.
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{iter::Enumerate, mem::MaybeUninit, ptr, slice};
+
+const FIRST_CHUNK_LEN_LOG2: usize = 5;
+const FIRST_CHUNK_LEN: usize = 1 << FIRST_CHUNK_LEN_LOG2;
+
+/// Locates `index` as `(chunk, chunk_len, offset)`.
+///
+/// # Panics
+///
+/// Iff `index` is too large to be addressed.
+fn locate(index: usize) -> (usize, usize, usize) {
+	let shifted = index
+		.checked_add(FIRST_CHUNK_LEN)
+		.expect("slot index overflow");
+	let chunk = (usize::BITS - 1 - shifted.leading_zeros()) as usize - FIRST_CHUNK_LEN_LOG2;
+	let chunk_len = FIRST_CHUNK_LEN << chunk;
+	(chunk, chunk_len, shifted - chunk_len)
+}
+
+/// Indexed storage that never moves its values.
+///
+/// Values are **not** dropped automatically.
+/// Owners have to call [`Slots::clear_in_place`] when dropped.
+pub(crate) struct Slots<T> {
+	chunks: Vec<Box<[MaybeUninit<T>]>>,
+	occupied: Vec<bool>,
+	vacant: Vec<usize>,
+	len: usize,
+}
+
+impl<T> Slots<T> {
+	pub(crate) const fn new() -> Self {
+		Self {
+			chunks: Vec::new(),
+			occupied: Vec::new(),
+			vacant: Vec::new(),
+			len: 0,
+		}
+	}
+
+	pub(crate) fn len(&self) -> usize {
+		self.len
+	}
+
+	/// One past the highest index that has ever been occupied.
+	pub(crate) fn end(&self) -> usize {
+		self.occupied.len()
+	}
+
+	/// The index that the next call to [`.insert`](`Slots::insert`) will return.
+	pub(crate) fn next_index(&self) -> usize {
+		self.vacant.last().copied().unwrap_or(self.occupied.len())
+	}
+
+	/// Stores `value` in a vacant slot, returning its index.
+	pub(crate) fn insert(&mut self, value: T) -> usize {
+		let index = if let Some(index) = self.vacant.pop() {
+			index
+		} else {
+			let index = self.occupied.len();
+			let (chunk, chunk_len, _) = locate(index);
+			if chunk == self.chunks.len() {
+				self.chunks
+					.push((0..chunk_len).map(|_| MaybeUninit::uninit()).collect());
+			}
+			self.occupied.push(false);
+			index
+		};
+
+		let (chunk, _, offset) = locate(index);
+		self.chunks[chunk][offset] = MaybeUninit::new(value);
+		self.occupied[index] = true;
+		self.len += 1;
+		index
+	}
+
+	/// Only for reading, as the pointer is derived from a shared reference.
+	fn slot(&self, index: usize) -> Option<*const T> {
+		if !*self.occupied.get(index)? {
+			return None;
+		}
+		let (chunk, _, offset) = locate(index);
+		Some(self.chunks[chunk][offset].as_ptr())
+	}
+
+	/// Derived from an exclusive reference to the chunk, so it may be written through.
+	fn slot_mut(&mut self, index: usize) -> Option<*mut T> {
+		if !*self.occupied.get(index)? {
+			return None;
+		}
+		let (chunk, _, offset) = locate(index);
+		// SAFETY: `locate` returns an `offset` below `chunk_len`, which is the length of that chunk.
+		Some(unsafe { self.chunks[chunk].as_mut_ptr().add(offset) }.cast::<T>())
+	}
+
+	pub(crate) fn get(&self, index: usize) -> Option<&T> {
+		// SAFETY: `.slot` only returns pointers to occupied, and so initialised, slots.
+		self.slot(index).map(|value| unsafe { &*value })
+	}
+
+	/// Retrieves a mutable reference to the value at `index`.
+	///
+	/// Whether that value may be moved out of is up to the owner.
+	pub(crate) fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+		// SAFETY: As in `.get`, and the pointer is derived from `&mut self`, so the reference is unique.
+		self.slot_mut(index).map(|value| unsafe { &mut *value })
+	}
+
+	/// Vacates the slot at `index` and returns its pointer, without dropping or reading the value.
+	fn vacate(&mut self, index: usize) -> Option<*mut T> {
+		let value = self.slot_mut(index)?;
+		self.occupied[index] = false;
+		self.vacant.push(index);
+		self.len -= 1;
+		Some(value)
+	}
+
+	/// Moves the value at `index` out.
+	pub(crate) fn take(&mut self, index: usize) -> Option<T> {
+		// SAFETY: The slot was occupied and is now vacant, so the value is read (moved out) exactly once.
+		self.vacate(index).map(|value| unsafe { value.read() })
+	}
+
+	/// Drops the value at `index` in place, returning whether there was one.
+	///
+	/// The slot is vacated first, so a panicking destructor leaks instead of double-dropping.
+	pub(crate) fn remove_in_place(&mut self, index: usize) -> bool {
+		match self.vacate(index) {
+			Some(value) => {
+				// SAFETY: The slot was occupied and is now vacant, so the value is dropped exactly once.
+				// It's dropped where it was stored, as the chunks are never reallocated.
+				unsafe { ptr::drop_in_place(value) };
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Drops all values in place, in index order.
+	pub(crate) fn clear_in_place(&mut self) {
+		for index in 0..self.end() {
+			self.remove_in_place(index);
+		}
+	}
+
+	pub(crate) fn iter(&self) -> Iter<'_, T> {
+		Iter {
+			chunks: self.chunks.iter(),
+			chunk: [].iter().enumerate(),
+			chunk_start: 0,
+			occupied: &self.occupied,
+		}
+	}
+
+	pub(crate) fn iter_mut(&mut self) -> IterMut<'_, T> {
+		IterMut {
+			chunks: self.chunks.iter_mut(),
+			chunk: [].iter_mut().enumerate(),
+			chunk_start: 0,
+			occupied: &self.occupied,
+		}
+	}
+}
+
+/// Iterates over the occupied slots of a [`Slots<T>`] as `(index, &T)`.
+pub(crate) struct Iter<'a, T> {
+	chunks: slice::Iter<'a, Box<[MaybeUninit<T>]>>,
+	chunk: Enumerate<slice::Iter<'a, MaybeUninit<T>>>,
+	chunk_start: usize,
+	occupied: &'a [bool],
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+	type Item = (usize, &'a T);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			for (offset, slot) in &mut self.chunk {
+				let index = self.chunk_start + offset;
+				if *self.occupied.get(index)? {
+					// SAFETY: Occupied slots are initialised.
+					return Some((index, unsafe { &*slot.as_ptr() }));
+				}
+			}
+
+			let chunk = self.chunks.next()?;
+			self.chunk_start = chunk.len() - FIRST_CHUNK_LEN;
+			self.chunk = chunk.iter().enumerate();
+		}
+	}
+}
+
+/// Iterates over the occupied slots of a [`Slots<T>`] as `(index, &mut T)`.
+pub(crate) struct IterMut<'a, T> {
+	chunks: slice::IterMut<'a, Box<[MaybeUninit<T>]>>,
+	chunk: Enumerate<slice::IterMut<'a, MaybeUninit<T>>>,
+	chunk_start: usize,
+	occupied: &'a [bool],
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+	type Item = (usize, &'a mut T);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			for (offset, slot) in &mut self.chunk {
+				let index = self.chunk_start + offset;
+				if *self.occupied.get(index)? {
+					// SAFETY: Occupied slots are initialised, and each slot is visited once.
+					return Some((index, unsafe { &mut *slot.as_mut_ptr() }));
+				}
+			}
+
+			let chunk = self.chunks.next()?;
+			self.chunk_start = chunk.len() - FIRST_CHUNK_LEN;
+			self.chunk = chunk.iter_mut().enumerate();
+		}
+	}
+}
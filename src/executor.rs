@@ -0,0 +1,245 @@
+//! A minimal single-threaded executor that polls its futures in place.
+//!
+//! [`LocalPool<F>`] stores all of its tasks inline in an [`ItemsPin<role::Items, HoleyVec<_>>`](`HoleyVec`),
+//! so futures that are [`!Unpin`](`Unpin`) are polled without boxing each of them.
+//! In exchange, all tasks of one pool share the future type `F`.
+//!
+//! Each task gets its own [`Waker`], which only reschedules that task.
+//! Once a task completes, its future is dropped in place and its output is kept until taken via [`TaskHandle`].
+//!
+//! No runtime or reactor is involved: [`.run`](`LocalPool::run`) parks the current thread
+//! while all remaining tasks are waiting to be woken.
+
+use crate::{collections::HoleyVec, role, ItemsPin};
+use alloc::{sync::Arc, task::Wake, vec::Vec};
+use core::{
+	fmt::{self, Debug, Formatter},
+	future::Future,
+	pin::Pin,
+	sync::atomic::{AtomicBool, Ordering},
+	task::{Context, Poll, Waker},
+};
+use std::{
+	sync::{Mutex, MutexGuard, PoisonError},
+	thread::{self, Thread},
+};
+
+/// Identifies a task spawned onto a [`LocalPool<F>`].
+///
+/// Handles stay unique per pool even after the task's slot is reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskHandle {
+	index: usize,
+	generation: u64,
+}
+
+/// Bookkeeping shared between a [`LocalPool<F>`] and all of its tasks' wakers.
+#[derive(Default)]
+struct Shared {
+	/// `(index, generation)` of each woken task, in wake order.
+	ready: Mutex<Vec<(usize, u64)>>,
+	/// The thread that's parked in [`LocalPool::run`], if any.
+	thread: Mutex<Option<Thread>>,
+}
+
+/// Locks `mutex` even if a previous holder panicked, as the guarded data stays consistent regardless.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+	mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+struct TaskWaker {
+	index: usize,
+	generation: u64,
+	/// Whether the task is in [`Shared::ready`] already.
+	queued: AtomicBool,
+	shared: Arc<Shared>,
+}
+
+impl Wake for TaskWaker {
+	fn wake(self: Arc<Self>) {
+		self.wake_by_ref()
+	}
+
+	fn wake_by_ref(self: &Arc<Self>) {
+		if !self.queued.swap(true, Ordering::AcqRel) {
+			lock(&self.shared.ready).push((self.index, self.generation));
+			if let Some(thread) = &*lock(&self.shared.thread) {
+				thread.unpark()
+			}
+		}
+	}
+}
+
+enum State<F: Future> {
+	Pending(F),
+	/// The output is not structurally pinned.
+	Done(Option<F::Output>),
+}
+
+/// A task, which stays pinned in its slot until its output is taken.
+struct Task<F: Future> {
+	state: State<F>,
+	waker: Arc<TaskWaker>,
+}
+
+impl<F: Future> Task<F> {
+	/// Polls the future if it's still pending, dropping it in place once it completes.
+	///
+	/// Returns whether the task completed during this call.
+	fn poll(self: Pin<&mut Self>) -> bool {
+		// SAFETY: Nothing is moved out of `this`. `state` is only replaced through `Pin::set` below,
+		// which drops the future in place, and `waker` isn't structurally pinned.
+		let this = unsafe { self.get_unchecked_mut() };
+		let State::Pending(future) = &mut this.state else {
+			return false;
+		};
+
+		this.waker.queued.store(false, Ordering::Release);
+		let waker = Waker::from(Arc::clone(&this.waker));
+		// SAFETY: The future is structurally pinned: the task is pinned, and the future is only dropped in place.
+		let future = unsafe { Pin::new_unchecked(future) };
+		match future.poll(&mut Context::from_waker(&waker)) {
+			Poll::Ready(output) => {
+				// Drops the future in place.
+				// SAFETY: `state` is structurally pinned along with the future, and `set` doesn't move the old value.
+				unsafe { Pin::new_unchecked(&mut this.state) }.set(State::Done(Some(output)));
+				true
+			}
+			Poll::Pending => false,
+		}
+	}
+}
+
+/// A single-threaded executor for futures of type `F`, which are polled in place without per-task boxing.
+///
+/// Pending tasks are dropped in place when the pool is dropped.
+pub struct LocalPool<F: Future> {
+	tasks: ItemsPin<role::Items, HoleyVec<Task<F>>>,
+	shared: Arc<Shared>,
+	next_generation: u64,
+	pending: usize,
+}
+
+impl<F: Future> LocalPool<F> {
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			tasks: ItemsPin::<role::Items, HoleyVec<_>>::pin(HoleyVec::new()),
+			shared: Arc::default(),
+			next_generation: 0,
+			pending: 0,
+		}
+	}
+
+	/// The number of tasks that haven't completed yet.
+	#[must_use]
+	pub fn pending(&self) -> usize {
+		self.pending
+	}
+
+	/// Moves `future` into the pool and schedules it to be polled.
+	///
+	/// The future isn't polled before [`.run_until_stalled`](`LocalPool::run_until_stalled`) or [`.run`](`LocalPool::run`) is called.
+	pub fn spawn(&mut self, future: F) -> TaskHandle {
+		let generation = self.next_generation;
+		self.next_generation += 1;
+
+		let index = self.tasks.next_index();
+		let waker = Arc::new(TaskWaker {
+			index,
+			generation,
+			queued: AtomicBool::new(false),
+			shared: Arc::clone(&self.shared),
+		});
+		waker.wake_by_ref();
+
+		let inserted = self.tasks.insert_pinned(Task {
+			state: State::Pending(future),
+			waker,
+		});
+		debug_assert_eq!(inserted, index);
+		self.pending += 1;
+		TaskHandle { index, generation }
+	}
+
+	fn get_pinned_mut(&mut self, handle: TaskHandle) -> Option<Pin<&mut Task<F>>> {
+		self.tasks
+			.get_pinned_mut(handle.index)
+			.filter(|task| task.waker.generation == handle.generation)
+	}
+
+	/// Whether the task has completed and its output is ready to be taken.
+	#[must_use]
+	pub fn is_finished(&self, handle: TaskHandle) -> bool {
+		matches!(
+			self.tasks.get_pinned(handle.index),
+			Some(task) if task.waker.generation == handle.generation && matches!(task.state, State::Done(_))
+		)
+	}
+
+	/// Takes the output of a completed task and frees its slot.
+	///
+	/// Returns [`None`] iff the task is still pending or its output was taken already.
+	pub fn take_output(&mut self, handle: TaskHandle) -> Option<F::Output> {
+		let task = self.get_pinned_mut(handle)?;
+		// SAFETY: Only the output is moved out, which isn't structurally pinned. The future was dropped already.
+		let State::Done(output) = &mut unsafe { task.get_unchecked_mut() }.state else {
+			return None;
+		};
+		let output = output.take();
+		self.tasks.remove_pinned(handle.index);
+		output
+	}
+
+	/// Polls woken tasks until none are left to poll, without blocking.
+	///
+	/// Tasks woken while this runs are polled again before it returns.
+	/// Completed futures are dropped in place right away.
+	pub fn run_until_stalled(&mut self) {
+		loop {
+			let ready = core::mem::take(&mut *lock(&self.shared.ready));
+			if ready.is_empty() {
+				break;
+			}
+
+			for (index, generation) in ready {
+				if let Some(task) = self.get_pinned_mut(TaskHandle { index, generation }) {
+					if task.poll() {
+						self.pending -= 1;
+					}
+				}
+			}
+		}
+	}
+
+	/// Polls tasks until all of them have completed, parking the current thread whenever they stall.
+	///
+	/// Wakers may be invoked from other threads to resume it.
+	pub fn run(&mut self) {
+		*lock(&self.shared.thread) = Some(thread::current());
+		loop {
+			self.run_until_stalled();
+			if self.pending == 0 {
+				break;
+			}
+			// An earlier `.unpark()` makes this return immediately, so no wake-up is lost.
+			thread::park();
+		}
+		*lock(&self.shared.thread) = None;
+	}
+}
+
+impl<F: Future> Default for LocalPool<F> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<F: Future> Debug for LocalPool<F> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("LocalPool")
+			.field("tasks", &self.tasks.len())
+			.field("pending", &self.pending)
+			.finish_non_exhaustive()
+	}
+}
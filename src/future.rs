@@ -0,0 +1,175 @@
+//! Futures that poll the pinned futures of a collection in place, and graceful asynchronous shutdown of pinned items.
+//!
+//! [`Race`] and [`TryJoinAll`] are returned by `.race_pinned` and `.try_join_all_pinned`
+//! on [`ItemsPin<role::Items, [F]>`](`crate::std_impls::slice`) and [`ItemsPin<role::Items, Vec<F>>`](`crate::std_impls::vec`).
+//!
+//! Each poll polls the collection's futures in index order, skipping those that completed already.
+//! Once the result is decided, a [`Vec<F>`](`alloc::vec::Vec`)'s futures are **all** dropped in place in index order,
+//! including those that completed earlier, before the result is returned, so the vector is empty afterwards.
+//! A slice's futures stay in place until the slice itself is dropped.
+//!
+//! The outputs of the futures aren't pinned.
+//!
+//! Items that implement [`PollClose`] can be closed concurrently through `.close_all_pinned`
+//! on [`ItemsPin<role::Items, Vec<T>>`](`crate::std_impls::vec`)
+//! and [`ItemsPin<role::Items, HoleyVec<T>>`](`crate::collections::holey_vec`) (with the `"alloc"` feature),
+//! which drop each item in place as soon as the collection allows after it reports that it's closed.
+
+use crate::{role, ItemsMut, ItemsPin, ItemsPinnedMut};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::{
+	future::Future,
+	pin::Pin,
+	task::{Context, Poll},
+};
+
+/// Asynchronous teardown for pinned items, to run before they are dropped.
+pub trait PollClose {
+	/// Attempts to close `self`, returning [`Poll::Ready`] once it is closed.
+	///
+	/// Once this returned [`Poll::Ready`], the same close operation doesn't call it again.
+	/// A new one (e.g. after a [`CloseAllPinned`](`crate::std_impls::vec::CloseAllPinned`) was dropped early) may,
+	/// in which case it should return [`Poll::Ready`] again.
+	fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()>;
+}
+
+/// Resolves to the index and output of the first of a collection's futures to complete.
+///
+/// Never resolves if the collection is empty.
+///
+/// # Panics
+///
+/// When polled again after it resolved.
+#[must_use = "futures do nothing unless polled"]
+pub struct Race<'a, C: ?Sized> {
+	futures: Option<&'a mut ItemsPin<role::Items, C>>,
+	settle: fn(&'a mut ItemsPin<role::Items, C>),
+}
+
+impl<'a, C: ?Sized> Race<'a, C> {
+	pub(crate) fn new(
+		futures: &'a mut ItemsPin<role::Items, C>,
+		settle: fn(&'a mut ItemsPin<role::Items, C>),
+	) -> Self {
+		Self {
+			futures: Some(futures),
+			settle,
+		}
+	}
+}
+
+impl<C: ?Sized + ItemsMut<role::Items>> Future for Race<'_, C>
+where
+	C::Item: Future,
+{
+	type Output = (usize, <C::Item as Future>::Output);
+
+	fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		let this = self.get_mut();
+		let futures = this
+			.futures
+			.as_mut()
+			.expect("`Race` polled after completion");
+
+		let winner =
+			futures
+				.items_pinned_mut()
+				.enumerate()
+				.find_map(|(index, future)| match future.poll(cx) {
+					Poll::Ready(output) => Some((index, output)),
+					Poll::Pending => None,
+				});
+
+		match winner {
+			Some(winner) => {
+				if let Some(futures) = this.futures.take() {
+					(this.settle)(futures)
+				}
+				Poll::Ready(winner)
+			}
+			None => Poll::Pending,
+		}
+	}
+}
+
+/// Resolves to the outputs of all of a collection's futures in index order,
+/// or to the index and error of the first of them to fail.
+///
+/// # Panics
+///
+/// When polled again after it resolved.
+#[cfg(feature = "alloc")]
+#[must_use = "futures do nothing unless polled"]
+pub struct TryJoinAll<'a, C: ?Sized, T> {
+	futures: Option<&'a mut ItemsPin<role::Items, C>>,
+	settle: fn(&'a mut ItemsPin<role::Items, C>),
+	/// Filled in lazily, as futures complete.
+	outputs: Vec<Option<T>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, C: ?Sized, T> TryJoinAll<'a, C, T> {
+	pub(crate) fn new(
+		futures: &'a mut ItemsPin<role::Items, C>,
+		settle: fn(&'a mut ItemsPin<role::Items, C>),
+	) -> Self {
+		Self {
+			futures: Some(futures),
+			settle,
+			outputs: Vec::new(),
+		}
+	}
+}
+
+/// The outputs are never pinned.
+#[cfg(feature = "alloc")]
+impl<C: ?Sized, T> Unpin for TryJoinAll<'_, C, T> {}
+
+#[cfg(feature = "alloc")]
+impl<C: ?Sized + ItemsMut<role::Items>, T, E> Future for TryJoinAll<'_, C, T>
+where
+	C::Item: Future<Output = Result<T, E>>,
+{
+	type Output = Result<Vec<T>, (usize, E)>;
+
+	fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		let this = self.get_mut();
+		let futures = this
+			.futures
+			.as_mut()
+			.expect("`TryJoinAll` polled after completion");
+
+		let mut pending = false;
+		let mut error = None;
+		for (index, future) in futures.items_pinned_mut().enumerate() {
+			if index == this.outputs.len() {
+				this.outputs.push(None);
+			}
+			if this.outputs[index].is_some() {
+				continue;
+			}
+
+			match future.poll(cx) {
+				Poll::Ready(Ok(output)) => this.outputs[index] = Some(output),
+				Poll::Ready(Err(e)) => {
+					error = Some((index, e));
+					break;
+				}
+				Poll::Pending => pending = true,
+			}
+		}
+
+		if pending && error.is_none() {
+			return Poll::Pending;
+		}
+
+		if let Some(futures) = this.futures.take() {
+			(this.settle)(futures)
+		}
+		Poll::Ready(match error {
+			Some(error) => Err(error),
+			None => Ok(this.outputs.drain(..).flatten().collect()),
+		})
+	}
+}
@@ -0,0 +1,449 @@
+//! Intrusive doubly linked lists over items that are pinned in place.
+//!
+//! Once items are pinned (for example in an [`ItemsPin<role::Items, Vec<T>>`](`crate::std_impls::vec`)),
+//! they can refer to each other by address.
+//! This module uses that to thread [`IntrusiveList`]s through [`Link`] fields embedded in the items themselves,
+//! so that e.g. LRU lists and wait queues don't need any allocations of their own.
+//!
+//! A [`Link`] unlinks itself when it is dropped,
+//! so items can be dropped in place (e.g. via `.pop_pinned` or `.truncate_pinned`) while still linked.
+//!
+//! References to linked items can't borrow from the collections that contain them through the list alone,
+//! which is why [`IntrusiveList::front`], [`IntrusiveList::back`] and [`IntrusiveList::iter`] are `unsafe`.
+//! Their `…_in` variants are safe and instead borrow the pinned slice in which the items are stored.
+//!
+//! All of this relies on linked items never being moved without being dropped first.
+//! [`Link`] isn't [`Unpin`], so [`ItemsPin`] doesn't hand out `&mut` references to the items that contain one:
+//!
+//! ```compile_fail
+//! use napje::{intrusive::{IntrusiveList, Link, Linked}, role, ItemsMut, ItemsPin, ItemsPinned};
+//! use std::{mem, pin::Pin};
+//!
+//! struct Entry {
+//!     link: Link,
+//! }
+//!
+//! unsafe impl Linked for Entry {
+//!     fn link(self: Pin<&Self>) -> Pin<&Link> {
+//!         unsafe { self.map_unchecked(|entry| &entry.link) }
+//!     }
+//! }
+//!
+//! let mut entries = ItemsPin::<role::Items, Vec<_>>::pin(vec![
+//!     Entry { link: Link::new() },
+//!     Entry { link: Link::new() },
+//! ]);
+//! let list = Box::pin(IntrusiveList::new());
+//! for entry in entries.items_pinned() {
+//!     list.as_ref().push_back(entry);
+//! }
+//!
+//! let mut items = entries.items_mut();
+//! mem::swap(items.next().unwrap(), items.next().unwrap());
+//! ```
+
+use crate::{role, ItemsPin};
+use core::{
+	cell::Cell,
+	fmt::{self, Debug, Formatter},
+	marker::{PhantomData, PhantomPinned},
+	ops::Range,
+	pin::Pin,
+	ptr::{self, NonNull},
+};
+
+/// A field type that lets its containing item be linked into an [`IntrusiveList`].
+///
+/// Unlinks itself when dropped.
+pub struct Link {
+	previous: Cell<Option<NonNull<Link>>>,
+	next: Cell<Option<NonNull<Link>>>,
+	owner: Cell<*const ()>,
+	_pinned: PhantomPinned,
+}
+
+impl Link {
+	#[must_use]
+	pub const fn new() -> Self {
+		Self {
+			previous: Cell::new(None),
+			next: Cell::new(None),
+			owner: Cell::new(ptr::null()),
+			_pinned: PhantomPinned,
+		}
+	}
+
+	/// Whether this [`Link`] is currently part of an [`IntrusiveList`].
+	#[must_use]
+	pub fn is_linked(&self) -> bool {
+		self.next.get().is_some()
+	}
+
+	/// Removes this [`Link`] from the [`IntrusiveList`] it's part of, if any.
+	pub fn unlink(self: Pin<&Self>) {
+		Pin::get_ref(self).unlink_inner()
+	}
+
+	fn unlink_inner(&self) {
+		if let (Some(previous), Some(next)) = (self.previous.take(), self.next.take()) {
+			unsafe {
+				previous.as_ref().next.set(Some(next));
+				next.as_ref().previous.set(Some(previous));
+			}
+		}
+	}
+
+	/// Inserts `self` between `previous` and `next`, which must be adjacent.
+	fn insert_between(&self, previous: NonNull<Link>, next: NonNull<Link>) {
+		let this = NonNull::from(self);
+		self.previous.set(Some(previous));
+		self.next.set(Some(next));
+		unsafe {
+			previous.as_ref().next.set(Some(this));
+			next.as_ref().previous.set(Some(this));
+		}
+	}
+}
+
+impl Default for Link {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Keeps the list consistent when pinned items are dropped in place.
+///
+/// Since pinned items can't be moved or forgotten without being dropped first (see [`Pin`]'s drop guarantee),
+/// a list never points at a [`Link`] whose memory was invalidated.
+impl Drop for Link {
+	fn drop(&mut self) {
+		self.unlink_inner()
+	}
+}
+
+impl Debug for Link {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Link")
+			.field("is_linked", &self.is_linked())
+			.finish()
+	}
+}
+
+/// Locates an item's [`Link`] for [`IntrusiveList<Self, Tag>`].
+///
+/// `Tag` can be used to implement this trait more than once for the same type,
+/// in order to link its instances into multiple kinds of list at the same time.
+///
+/// # Safety
+///
+/// [`.link`](`Linked::link`) must always return the same [`Link`],
+/// which must be a (structurally pinned) field of `self`.
+///
+/// That [`Link`] must not be exposed in any other way that would allow it to be linked into a list
+/// (including [`IntrusiveList`]s for another `Tag`).
+pub unsafe trait Linked<Tag = ()> {
+	fn link(self: Pin<&Self>) -> Pin<&Link>;
+}
+
+/// An intrusive doubly linked list of `T`s that are pinned in place elsewhere.
+///
+/// The list itself must be pinned before items can be linked into it.
+/// Dropping the list unlinks all of its items.
+pub struct IntrusiveList<T: Linked<Tag>, Tag = ()> {
+	/// Sentinel of the circular list, if anchored.
+	head: Link,
+	_items: PhantomData<*const T>,
+	_tag: PhantomData<fn(Tag)>,
+}
+
+impl<T: Linked<Tag>, Tag> IntrusiveList<T, Tag> {
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			head: Link::new(),
+			_items: PhantomData,
+			_tag: PhantomData,
+		}
+	}
+
+	/// The sentinel [`Link`], anchored to itself if the list was empty.
+	fn head(self: Pin<&Self>) -> NonNull<Link> {
+		let head = &Pin::get_ref(self).head;
+		let this = NonNull::from(head);
+		if head.next.get().is_none() {
+			head.previous.set(Some(this));
+			head.next.set(Some(this));
+		}
+		this
+	}
+
+	fn link_item(item: Pin<&T>) -> &Link {
+		let link = Pin::get_ref(item.link());
+		link.unlink_inner();
+		link.owner.set((Pin::get_ref(item) as *const T).cast());
+		link
+	}
+
+	/// Links `item` at the back of this list.
+	///
+	/// If `item` is already linked (into this or another list for the same `Tag`), it is moved here.
+	///
+	/// `item` stays linked until it's unlinked or dropped, which is sound only because it's pinned:
+	/// its memory can't be reused or moved out of before its [`Link`]'s [`Drop`] implementation runs.
+	pub fn push_back(self: Pin<&Self>, item: Pin<&T>) {
+		let head = self.head();
+		let last = unsafe { head.as_ref() }.previous.get().unwrap_or(head);
+		Self::link_item(item).insert_between(last, head)
+	}
+
+	/// Links `item` at the front of this list.
+	///
+	/// If `item` is already linked (into this or another list for the same `Tag`), it is moved here.
+	///
+	/// `item` stays linked until it's unlinked or dropped, which is sound only because it's pinned:
+	/// its memory can't be reused or moved out of before its [`Link`]'s [`Drop`] implementation runs.
+	pub fn push_front(self: Pin<&Self>, item: Pin<&T>) {
+		let head = self.head();
+		let first = unsafe { head.as_ref() }.next.get().unwrap_or(head);
+		Self::link_item(item).insert_between(head, first)
+	}
+
+	/// Unlinks the first item, returning whether there was one.
+	#[allow(clippy::must_use_candidate)] // Modifies the list through shared references.
+	pub fn unlink_front(self: Pin<&Self>) -> bool {
+		match self.first_link() {
+			Some(link) => {
+				unsafe { link.as_ref() }.unlink_inner();
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Unlinks the last item, returning whether there was one.
+	#[allow(clippy::must_use_candidate)] // Modifies the list through shared references.
+	pub fn unlink_back(self: Pin<&Self>) -> bool {
+		match self.last_link() {
+			Some(link) => {
+				unsafe { link.as_ref() }.unlink_inner();
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Unlinks all items.
+	pub fn clear(self: Pin<&Self>) {
+		while self.unlink_front() {}
+	}
+
+	#[must_use]
+	pub fn is_empty(self: Pin<&Self>) -> bool {
+		self.first_link().is_none()
+	}
+
+	/// Counts the linked items.
+	#[must_use]
+	pub fn len(self: Pin<&Self>) -> usize {
+		self.links().count()
+	}
+
+	fn first_link(self: Pin<&Self>) -> Option<NonNull<Link>> {
+		let head = &Pin::get_ref(self).head;
+		head.next
+			.get()
+			.filter(|&first| first != NonNull::from(head))
+	}
+
+	fn last_link(self: Pin<&Self>) -> Option<NonNull<Link>> {
+		let head = &Pin::get_ref(self).head;
+		head.previous
+			.get()
+			.filter(|&last| last != NonNull::from(head))
+	}
+
+	fn links(self: Pin<&Self>) -> Links {
+		let head = NonNull::from(&Pin::get_ref(self).head);
+		Links {
+			head,
+			current: head,
+		}
+	}
+
+	/// Retrieves the first item.
+	///
+	/// # Safety
+	///
+	/// The item must not be dropped while the returned reference is in use.
+	#[must_use]
+	pub unsafe fn front<'a>(self: Pin<&Self>) -> Option<Pin<&'a T>> {
+		self.first_link().map(|link| owner(link))
+	}
+
+	/// Retrieves the last item.
+	///
+	/// # Safety
+	///
+	/// The item must not be dropped while the returned reference is in use.
+	#[must_use]
+	pub unsafe fn back<'a>(self: Pin<&Self>) -> Option<Pin<&'a T>> {
+		self.last_link().map(|link| owner(link))
+	}
+
+	/// Iterates over the linked items from front to back.
+	///
+	/// # Safety
+	///
+	/// No item may be dropped while any reference returned by the iterator is in use,
+	/// and the item returned last must not be dropped before the next call to `.next()`.
+	///
+	/// Modifying the list during iteration is otherwise allowed,
+	/// but may cause items to be skipped or visited repeatedly.
+	#[must_use]
+	pub unsafe fn iter(self: Pin<&Self>) -> Iter<'_, T> {
+		Iter {
+			links: self.links(),
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Retrieves the first item, iff it is stored in `items`.
+	#[must_use]
+	pub fn front_in<'a>(
+		self: Pin<&Self>,
+		items: &'a ItemsPin<role::Items, [T]>,
+	) -> Option<Pin<&'a T>> {
+		let link = self.first_link()?;
+		unsafe { owner_in(link, &items.as_ptr_range()) }
+	}
+
+	/// Retrieves the last item, iff it is stored in `items`.
+	#[must_use]
+	pub fn back_in<'a>(
+		self: Pin<&Self>,
+		items: &'a ItemsPin<role::Items, [T]>,
+	) -> Option<Pin<&'a T>> {
+		let link = self.last_link()?;
+		unsafe { owner_in(link, &items.as_ptr_range()) }
+	}
+
+	/// Iterates over the linked items from front to back,
+	/// skipping those that aren't stored in `items`.
+	///
+	/// Modifying the list during iteration is safe,
+	/// but may cause items to be skipped or visited repeatedly.
+	#[must_use]
+	pub fn iter_in<'a>(
+		self: Pin<&'a Self>,
+		items: &'a ItemsPin<role::Items, [T]>,
+	) -> IterIn<'a, T> {
+		IterIn {
+			links: self.links(),
+			range: items.as_ptr_range(),
+			_phantom: PhantomData,
+		}
+	}
+}
+
+impl<T: Linked<Tag>, Tag> Default for IntrusiveList<T, Tag> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Linked<Tag>, Tag> Drop for IntrusiveList<T, Tag> {
+	fn drop(&mut self) {
+		// The list can only have been anchored while pinned, so it's still in the same place.
+		unsafe { Pin::new_unchecked(&*self) }.clear()
+	}
+}
+
+impl<T: Linked<Tag>, Tag> Debug for IntrusiveList<T, Tag> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("IntrusiveList").finish()
+	}
+}
+
+/// Reads the address of `link`'s owner without creating a reference to it.
+///
+/// # Safety
+///
+/// `link` must be linked into an [`IntrusiveList<T, _>`].
+unsafe fn owner_ptr<T>(link: NonNull<Link>) -> *const T {
+	(*ptr::addr_of!((*link.as_ptr()).owner)).get().cast()
+}
+
+/// # Safety
+///
+/// `link` must be linked into an [`IntrusiveList<T, _>`].
+unsafe fn owner<'a, T>(link: NonNull<Link>) -> Pin<&'a T> {
+	Pin::new_unchecked(&*owner_ptr(link))
+}
+
+/// Borrows `link`'s owner only if it's stored within `range`.
+///
+/// Owners elsewhere may be borrowed exclusively at the same time, so they must not be referenced at all.
+///
+/// # Safety
+///
+/// `link` must be linked into an [`IntrusiveList<T, _>`], and `range` must be that of a borrowed [`ItemsPin<role::Items, [T]>`].
+unsafe fn owner_in<'a, T>(link: NonNull<Link>, range: &Range<*const T>) -> Option<Pin<&'a T>> {
+	let owner = owner_ptr::<T>(link);
+	range.contains(&owner).then(|| Pin::new_unchecked(&*owner))
+}
+
+/// Walks [`Link`]s starting after `current`, which must stay alive until the next call to `.next()`.
+///
+/// The next [`Link`] is only looked up at that point,
+/// so the one most recently returned is the only one that matters in this regard.
+struct Links {
+	head: NonNull<Link>,
+	current: NonNull<Link>,
+}
+
+impl Iterator for Links {
+	type Item = NonNull<Link>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		// Only the `next` field is referenced, as the rest of the item may be borrowed exclusively elsewhere.
+		let next = unsafe { &*ptr::addr_of!((*self.current.as_ptr()).next) }
+			.get()
+			.filter(|&next| next != self.head)?;
+		self.current = next;
+		Some(next)
+	}
+}
+
+/// An iterator over the items of an [`IntrusiveList`], as returned by [`IntrusiveList::iter`].
+pub struct Iter<'a, T> {
+	links: Links,
+	_phantom: PhantomData<Pin<&'a T>>,
+}
+
+impl<'a, T: 'a> Iterator for Iter<'a, T> {
+	type Item = Pin<&'a T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.links.next().map(|link| unsafe { owner(link) })
+	}
+}
+
+/// An iterator over the items of an [`IntrusiveList`] that are stored in a particular slice,
+/// as returned by [`IntrusiveList::iter_in`].
+pub struct IterIn<'a, T> {
+	links: Links,
+	range: Range<*const T>,
+	_phantom: PhantomData<Pin<&'a T>>,
+}
+
+impl<'a, T: 'a> Iterator for IterIn<'a, T> {
+	type Item = Pin<&'a T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		// Items outside of `range` are only located by address and never referenced,
+		// as they may be borrowed exclusively elsewhere.
+		// Items inside of it are borrowed through `items`, so none of them can be dropped before this returns.
+		let range = &self.range;
+		self.links.find_map(|link| unsafe { owner_in(link, range) })
+	}
+}
@@ -0,0 +1,102 @@
+//! The lifetime-parametrised item traits from before the switch to generic associated types, for migration.
+//!
+//! Each of these traits is blanket-implemented for all implementors of its current counterpart,
+//! so generic code written against e.g. `C: Items<'a, R>` keeps working after importing these traits instead.
+//!
+//! Collection implementations can't be migrated this way and must be ported to the current traits directly:
+//!
+//! - `impl<'a, …> Items<'a, R> for C` becomes `impl<…> Items<R> for C`.
+//! - `type ItemsIter = Iter<'a, T>;` becomes `type ItemsIter<'a> = Iter<'a, T> where Self: 'a;`.
+//! - `fn items(&'a self) -> Self::ItemsIter` becomes `fn items(&self) -> Self::ItemsIter<'_>`.
+//!
+//! The same applies to [`ItemsPinned`], [`ItemsMut`] and [`ItemsPinnedMut`].
+
+#![allow(deprecated)]
+
+use core::pin::Pin;
+
+#[deprecated = "Use `napje::Items` instead, which has no trait-level lifetime."]
+pub trait Items<'a, R> {
+	type Item: 'a;
+	type ItemsIter: 'a + Iterator<Item = &'a Self::Item>;
+
+	fn items(&'a self) -> Self::ItemsIter;
+}
+
+impl<'a, R, C: ?Sized> Items<'a, R> for C
+where
+	C: 'a + crate::Items<R>,
+	C::Item: 'a,
+	C::ItemsIter<'a>: 'a,
+{
+	type Item = C::Item;
+	type ItemsIter = C::ItemsIter<'a>;
+
+	fn items(&'a self) -> Self::ItemsIter {
+		crate::Items::items(self)
+	}
+}
+
+#[deprecated = "Use `napje::ItemsPinned` instead, which has no trait-level lifetime."]
+pub trait ItemsPinned<'a, R>: Items<'a, R> {
+	type ItemsPinnedIter: 'a + Iterator<Item = Pin<&'a Self::Item>>;
+
+	fn items_pinned(&'a self) -> Self::ItemsPinnedIter;
+}
+
+impl<'a, R, C: ?Sized> ItemsPinned<'a, R> for C
+where
+	C: 'a + crate::ItemsPinned<R>,
+	C::Item: 'a,
+	C::ItemsIter<'a>: 'a,
+	C::ItemsPinnedIter<'a>: 'a,
+{
+	type ItemsPinnedIter = C::ItemsPinnedIter<'a>;
+
+	fn items_pinned(&'a self) -> Self::ItemsPinnedIter {
+		crate::ItemsPinned::items_pinned(self)
+	}
+}
+
+#[deprecated = "Use `napje::ItemsMut` instead, which has no trait-level lifetime."]
+pub trait ItemsMut<'a, R>: Items<'a, R> {
+	type ItemsMutIter: 'a + Iterator<Item = &'a mut Self::Item>;
+
+	fn items_mut(&'a mut self) -> Self::ItemsMutIter;
+}
+
+impl<'a, R, C: ?Sized> ItemsMut<'a, R> for C
+where
+	C: 'a + crate::ItemsMut<R>,
+	C::Item: 'a,
+	C::ItemsIter<'a>: 'a,
+	C::ItemsMutIter<'a>: 'a,
+{
+	type ItemsMutIter = C::ItemsMutIter<'a>;
+
+	fn items_mut(&'a mut self) -> Self::ItemsMutIter {
+		crate::ItemsMut::items_mut(self)
+	}
+}
+
+#[deprecated = "Use `napje::ItemsPinnedMut` instead, which has no trait-level lifetime."]
+pub trait ItemsPinnedMut<'a, R>: ItemsPinned<'a, R> {
+	type ItemsPinnedMutIter: 'a + Iterator<Item = Pin<&'a mut Self::Item>>;
+
+	fn items_pinned_mut(&'a mut self) -> Self::ItemsPinnedMutIter;
+}
+
+impl<'a, R, C: ?Sized> ItemsPinnedMut<'a, R> for C
+where
+	C: 'a + crate::ItemsPinnedMut<R>,
+	C::Item: 'a,
+	C::ItemsIter<'a>: 'a,
+	C::ItemsPinnedIter<'a>: 'a,
+	C::ItemsPinnedMutIter<'a>: 'a,
+{
+	type ItemsPinnedMutIter = C::ItemsPinnedMutIter<'a>;
+
+	fn items_pinned_mut(&'a mut self) -> Self::ItemsPinnedMutIter {
+		crate::ItemsPinnedMut::items_pinned_mut(self)
+	}
+}
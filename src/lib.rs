@@ -2,19 +2,49 @@
 #![warn(clippy::pedantic)]
 #![allow(clippy::semicolon_if_nothing_returned)]
 #![allow(clippy::inline_always)] // `const fn` with added constraints is unstable.
+#![allow(clippy::ref_as_ptr)] // `ptr::from_ref` and `ptr::from_mut` are too new for the MSRV.
+#![no_std]
 
-use role::Role;
-use std::{
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+use core::{
+	any::type_name,
+	fmt::{self, Debug, DebugStruct, Formatter},
 	marker::PhantomData,
+	mem::ManuallyDrop,
 	ops::{Deref, DerefMut},
 	pin::Pin,
+	ptr,
+};
+#[cfg(feature = "rayon")]
+use rayon::iter::{
+	plumbing::{Consumer, ProducerCallback, UnindexedConsumer},
+	IndexedParallelIterator, ParallelIterator,
 };
+use role::Role;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer};
 
 #[cfg(doctest)]
 pub mod readme {
 	doc_comment::doctest!("../README.md");
 }
 
+pub mod cell;
+#[cfg(feature = "check-pins")]
+mod check_pins;
+pub mod collections;
+#[cfg(feature = "executor")]
+pub mod executor;
+pub mod future;
+pub mod intrusive;
+pub mod legacy;
+#[cfg(feature = "futures")]
+pub mod sink;
 pub mod std_impls;
 
 pub mod role {
@@ -37,12 +67,14 @@ pub mod role {
 
 /// A pinning wrapper for a collection type `C` that can pin-project to its items while pinned this way.
 ///
-/// Unlike when using [`Pin<&C>`](`std::pin::Pin`), this allows the collection itself to stay [`Unpin`].
+/// Unlike when using [`Pin<&C>`](`core::pin::Pin`), this allows the collection itself to stay [`Unpin`].
 ///
-/// [`ItemsPin<R, C>`] acts to [`C: Items<Item = T>`](`Items`) as [`Pin<P>`](`std::pin::Pin`) does to [`P: Deref<Target = T>`](`std::ops::Deref`).
+/// [`ItemsPin<R, C>`] acts to [`C: Items<Item = T>`](`Items`) as [`Pin<P>`](`core::pin::Pin`) does to [`P: Deref<Target = T>`](`core::ops::Deref`).
 ///
 /// `#[repr(transparent)]` towards `C`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+///
+/// Implements [`Drop`] (which only does something with the `"check-pins"` feature), so it isn't [`Copy`].
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
 pub struct ItemsPin<R, C: ?Sized> {
 	_role: PhantomData<R>,
@@ -51,9 +83,9 @@ pub struct ItemsPin<R, C: ?Sized> {
 
 //TODO: Allow creation with custom roles that don't come with any pre-implemented traits, functions or methods.
 
-impl<'a, R: Role, C: Sized> ItemsPin<R, C>
+impl<R: Role, C: Sized> ItemsPin<R, C>
 where
-	C: Items<'a, R>,
+	C: Items<R>,
 	C::Item: Unpin,
 {
 	#[inline(always)]
@@ -67,12 +99,15 @@ where
 	/// so that we can ignore the pinning invariants when unwrapping it.
 	#[inline(always)]
 	pub fn into_inner(items_pin: ItemsPin<R, C>) -> C {
-		items_pin.collection
+		items_pin.into_collection()
 	}
 
 	#[inline(always)]
 	pub fn new_ref(collection: &C) -> &ItemsPin<R, C> {
-		unsafe { &*(collection as *const C).cast() }
+		let items_pin = unsafe { &*(collection as *const C).cast() };
+		#[cfg(feature = "check-pins")]
+		check_pins::forget(items_pin);
+		items_pin
 	}
 
 	/// Unwraps this [`ItemsPin<C>`], returning the underlying collection.
@@ -86,7 +121,10 @@ where
 
 	#[inline(always)]
 	pub fn new_mut(collection: &mut C) -> &mut ItemsPin<R, C> {
-		unsafe { &mut *(collection as *mut C).cast() }
+		let items_pin = unsafe { &mut *(collection as *mut C).cast() };
+		#[cfg(feature = "check-pins")]
+		check_pins::forget(items_pin);
+		items_pin
 	}
 
 	/// Unwraps this [`ItemsPin<C>`], returning the underlying collection.
@@ -95,13 +133,15 @@ where
 	/// so that we can ignore the pinning invariants when unwrapping it.
 	#[inline(always)]
 	pub fn inner_mut(items_pin: &mut ItemsPin<R, C>) -> &mut C {
+		#[cfg(feature = "check-pins")]
+		check_pins::forget(items_pin);
 		&mut items_pin.collection
 	}
 }
 
-impl<'a, R: Role, C: Sized> ItemsPin<R, C>
+impl<R: Role, C: Sized> ItemsPin<R, C>
 where
-	C: Items<'a, R>,
+	C: Items<R>,
 {
 	/// Constructs a new [`ItemsPin<P>`] around a collection of items of a type that may or may not implement [`Unpin`].
 	///
@@ -112,6 +152,8 @@ where
 	/// See [`Pin::new_unchecked`].
 	#[inline(always)]
 	pub unsafe fn new_unchecked(collection: C) -> ItemsPin<R, C> {
+		#[cfg(feature = "check-pins")]
+		check_pins::register::<R, C>(Self::item_addresses_at);
 		Self {
 			_role: PhantomData,
 			collection,
@@ -125,7 +167,7 @@ where
 	/// See [`Pin::into_inner_unchecked`].
 	#[inline(always)]
 	pub unsafe fn into_inner_unchecked(items_pin: ItemsPin<R, C>) -> C {
-		items_pin.collection
+		items_pin.into_collection()
 	}
 
 	/// Constructs a new [`ItemsPin<P>`] around a collection of items of a type that may or may not implement [`Unpin`].
@@ -167,35 +209,72 @@ where
 	/// # Safety
 	///
 	/// See [`Pin::into_inner_unchecked`].
+	///
+	/// With the `"check-pins"` feature, the next pinned access or dropping `items_pin` in place panics
+	/// if items were moved through the returned reference.
 	#[inline(always)]
 	pub unsafe fn inner_mut_unchecked(items_pin: &mut ItemsPin<R, C>) -> &mut C {
+		#[cfg(feature = "check-pins")]
+		{
+			check_pins::register::<R, C>(Self::item_addresses_at);
+			items_pin.observe_pins(true);
+		}
 		&mut items_pin.collection
 	}
+
+	/// # Safety
+	///
+	/// `address` must be that of a live `ItemsPin<R, C>`.
+	#[cfg(feature = "check-pins")]
+	unsafe fn item_addresses_at(address: usize) -> alloc::vec::Vec<usize> {
+		(*(address as *const Self)).item_addresses()
+	}
 }
 
-pub trait Items<'a, R> {
-	type Item: 'a;
-	type ItemsIter: 'a + Iterator<Item = &'a Self::Item>;
+pub trait Items<R> {
+	type Item;
+	type ItemsIter<'a>: Iterator<Item = &'a Self::Item>
+	where
+		Self: 'a,
+		Self::Item: 'a;
+
+	fn items(&self) -> Self::ItemsIter<'_>;
+}
 
-	fn items(&'a self) -> Self::ItemsIter;
+/// Enables [`ItemsPin`]'s [`Debug`] implementation.
+///
+/// Collections without details to show can implement this with an empty body.
+pub trait ItemsDebug<R>: Items<R> {
+	/// Adds collection-specific details (like `len` and `capacity`) to [`ItemsPin`]'s [`Debug`] output.
+	fn debug_fields(&self, _debug_struct: &mut DebugStruct) {}
 }
 
-pub trait ItemsPinned<'a, R>: Items<'a, R> {
-	type ItemsPinnedIter: 'a + Iterator<Item = Pin<&'a Self::Item>>;
+pub trait ItemsPinned<R>: Items<R> {
+	type ItemsPinnedIter<'a>: Iterator<Item = Pin<&'a Self::Item>>
+	where
+		Self: 'a,
+		Self::Item: 'a;
 
-	fn items_pinned(&'a self) -> Self::ItemsPinnedIter;
+	fn items_pinned(&self) -> Self::ItemsPinnedIter<'_>;
 }
 
-pub trait ItemsMut<'a, R>: Items<'a, R> {
-	type ItemsMutIter: 'a + Iterator<Item = &'a mut Self::Item>;
+pub trait ItemsMut<R>: Items<R> {
+	type ItemsMutIter<'a>: Iterator<Item = &'a mut Self::Item>
+	where
+		Self: 'a,
+		Self::Item: 'a;
 
-	fn items_mut(&'a mut self) -> Self::ItemsMutIter;
+	fn items_mut(&mut self) -> Self::ItemsMutIter<'_>;
 }
 
-pub trait ItemsPinnedMut<'a, R>: ItemsPinned<'a, R> + ItemsMut<'a, R> {
-	type ItemsPinnedMutIter: 'a + Iterator<Item = Pin<&'a mut Self::Item>>;
+/// Doesn't require [`ItemsMut`], as pinned collections like [`ItemsPin<R, C>`] must not hand out `&mut` items that aren't [`Unpin`].
+pub trait ItemsPinnedMut<R>: ItemsPinned<R> {
+	type ItemsPinnedMutIter<'a>: Iterator<Item = Pin<&'a mut Self::Item>>
+	where
+		Self: 'a,
+		Self::Item: 'a;
 
-	fn items_pinned_mut(&'a mut self) -> Self::ItemsPinnedMutIter;
+	fn items_pinned_mut(&mut self) -> Self::ItemsPinnedMutIter<'_>;
 }
 
 #[repr(transparent)]
@@ -238,51 +317,240 @@ where
 	}
 }
 
-impl<'a, R: Role, C: ?Sized> Items<'a, R> for ItemsPin<R, C>
+/// The parallel counterpart to [`PinIter`].
+#[cfg(feature = "rayon")]
+#[repr(transparent)]
+pub struct PinParIter<Iter> {
+	iter: Iter,
+}
+
+#[cfg(feature = "rayon")]
+impl<Iter> PinParIter<Iter>
 where
-	C: Items<'a, R>,
+	Iter: ParallelIterator,
+	Iter::Item: Deref,
+{
+	pub fn new(iter: Iter) -> Self
+	where
+		<Iter::Item as Deref>::Target: Unpin,
+	{
+		Self { iter }
+	}
+
+	/// # Safety
+	///
+	/// Only safe iff all pinning invariants are upheld when each of `Iter's` [`ParallelIterator::Item`]s is wrapped in [`Pin<_>`].
+	pub unsafe fn new_unchecked(iter: Iter) -> Self {
+		Self { iter }
+	}
+}
+
+#[cfg(feature = "rayon")]
+impl<Iter> ParallelIterator for PinParIter<Iter>
+where
+	Iter: ParallelIterator,
+	Iter::Item: Deref,
+{
+	type Item = Pin<Iter::Item>;
+
+	fn drive_unindexed<C>(self, consumer: C) -> C::Result
+	where
+		C: UnindexedConsumer<Self::Item>,
+	{
+		self.iter
+			.map(|item| unsafe { Pin::new_unchecked(item) })
+			.drive_unindexed(consumer)
+	}
+
+	#[inline(always)]
+	fn opt_len(&self) -> Option<usize> {
+		self.iter.opt_len()
+	}
+}
+
+#[cfg(feature = "rayon")]
+impl<Iter> IndexedParallelIterator for PinParIter<Iter>
+where
+	Iter: IndexedParallelIterator,
+	Iter::Item: Deref,
+{
+	fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> C::Result {
+		self.iter
+			.map(|item| unsafe { Pin::new_unchecked(item) })
+			.drive(consumer)
+	}
+
+	#[inline(always)]
+	fn len(&self) -> usize {
+		self.iter.len()
+	}
+
+	fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> CB::Output {
+		self.iter
+			.map(|item| unsafe { Pin::new_unchecked(item) })
+			.with_producer(callback)
+	}
+}
+
+impl<R: Role, C: ?Sized> Items<R> for ItemsPin<R, C>
+where
+	C: Items<R>,
 {
 	type Item = C::Item;
-	type ItemsIter = C::ItemsIter;
+	type ItemsIter<'a>
+		= C::ItemsIter<'a>
+	where
+		Self: 'a,
+		Self::Item: 'a;
 
-	fn items(&'a self) -> Self::ItemsIter {
+	fn items(&self) -> Self::ItemsIter<'_> {
 		self.collection.items()
 	}
 }
 
-impl<'a, R: Role, C: ?Sized> ItemsPinned<'a, R> for ItemsPin<R, C>
+impl<R: Role, C: ?Sized> ItemsDebug<R> for ItemsPin<R, C>
 where
-	C: Items<'a, R>,
+	C: ItemsDebug<R>,
 {
-	type ItemsPinnedIter = PinIter<C::ItemsIter>;
+	fn debug_fields(&self, debug_struct: &mut DebugStruct) {
+		self.collection.debug_fields(debug_struct)
+	}
+}
 
-	fn items_pinned(&'a self) -> Self::ItemsPinnedIter {
+impl<R: Role, C: ?Sized> ItemsPinned<R> for ItemsPin<R, C>
+where
+	C: Items<R>,
+{
+	type ItemsPinnedIter<'a>
+		= PinIter<C::ItemsIter<'a>>
+	where
+		Self: 'a,
+		Self::Item: 'a;
+
+	fn items_pinned(&self) -> Self::ItemsPinnedIter<'_> {
+		#[cfg(feature = "check-pins")]
+		self.check_pins();
 		unsafe { PinIter::new_unchecked(self.collection.items()) }
 	}
 }
 
-impl<'a, R: Role, C: ?Sized> ItemsMut<'a, R> for ItemsPin<R, C>
+#[cfg(feature = "check-pins")]
+impl<R: Role, C: ?Sized + Items<R>> ItemsPin<R, C> {
+	fn item_addresses(&self) -> alloc::vec::Vec<usize> {
+		self.collection
+			.items()
+			.map(|item| (item as *const C::Item).cast::<()>() as usize)
+			.collect()
+	}
+
+	fn observe_pins(&self, unchecked: bool) {
+		if core::mem::size_of::<C::Item>() != 0 {
+			check_pins::observe(self, self.item_addresses(), unchecked)
+		}
+	}
+
+	/// Panics iff items were moved since they were last observed pinned, then records their current addresses.
+	pub(crate) fn check_pins(&self) {
+		self.observe_pins(false)
+	}
+
+	/// Records the current item addresses after items were dropped in place, so that their memory may be reused.
+	pub(crate) fn record_pins(&self) {
+		self.observe_pins(false)
+	}
+}
+
+/// Panics with the `"check-pins"` feature iff recorded items were moved or not dropped in place, unless already panicking.
+impl<R, C: ?Sized> Drop for ItemsPin<R, C> {
+	fn drop(&mut self) {
+		#[cfg(feature = "check-pins")]
+		check_pins::check_on_drop(self)
+	}
+}
+
+impl<R, C> ItemsPin<R, C> {
+	/// Moves the collection out, which is possible despite the [`Drop`] implementation.
+	fn into_collection(self) -> C {
+		#[cfg(feature = "check-pins")]
+		check_pins::forget(&self);
+		let this = ManuallyDrop::new(self);
+		unsafe { ptr::read(ptr::addr_of!(this.collection)) }
+	}
+}
+
+/// Only for [`Unpin`] items, like [`DerefMut`]:
+///
+/// ```compile_fail
+/// use napje::{role, ItemsMut, ItemsPin};
+/// use std::marker::PhantomPinned;
+///
+/// let mut pinned = ItemsPin::<role::Items, Vec<_>>::pin(vec![PhantomPinned, PhantomPinned]);
+/// let _ = pinned.items_mut();
+/// ```
+impl<R: Role, C: ?Sized> ItemsMut<R> for ItemsPin<R, C>
 where
-	C: ItemsMut<'a, R>,
+	C: ItemsMut<R>,
+	C::Item: Unpin,
 {
-	type ItemsMutIter = C::ItemsMutIter;
+	type ItemsMutIter<'a>
+		= C::ItemsMutIter<'a>
+	where
+		Self: 'a,
+		Self::Item: 'a;
 
-	fn items_mut(&'a mut self) -> Self::ItemsMutIter {
+	fn items_mut(&mut self) -> Self::ItemsMutIter<'_> {
+		#[cfg(feature = "check-pins")]
+		check_pins::forget(self);
 		self.collection.items_mut()
 	}
 }
 
-impl<'a, R: Role, C: ?Sized> ItemsPinnedMut<'a, R> for ItemsPin<R, C>
+impl<R: Role, C: ?Sized> ItemsPinnedMut<R> for ItemsPin<R, C>
 where
-	C: ItemsMut<'a, R>,
+	C: ItemsMut<R>,
 {
-	type ItemsPinnedMutIter = PinIter<C::ItemsMutIter>;
+	type ItemsPinnedMutIter<'a>
+		= PinIter<C::ItemsMutIter<'a>>
+	where
+		Self: 'a,
+		Self::Item: 'a;
 
-	fn items_pinned_mut(&'a mut self) -> Self::ItemsPinnedMutIter {
+	fn items_pinned_mut(&mut self) -> Self::ItemsPinnedMutIter<'_> {
+		#[cfg(feature = "check-pins")]
+		self.check_pins();
 		unsafe { PinIter::new_unchecked(self.collection.items_mut()) }
 	}
 }
 
+/// Formats as e.g. `ItemsPin<Values> { items: [...] }`,
+/// with the items listed through [`Items::items`] after the fields from [`ItemsDebug::debug_fields`].
+impl<R: Role, C: ?Sized> Debug for ItemsPin<R, C>
+where
+	C: ItemsDebug<R>,
+	C::Item: Debug,
+{
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		struct DebugItems<'a, R, C: ?Sized>(&'a C, PhantomData<R>);
+		impl<R, C: ?Sized> Debug for DebugItems<'_, R, C>
+		where
+			C: Items<R>,
+			C::Item: Debug,
+		{
+			fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+				f.debug_list().entries(self.0.items()).finish()
+			}
+		}
+
+		let role = type_name::<R>();
+		write!(f, "ItemsPin<{}>", role.rsplit("::").next().unwrap_or(role))?;
+		let mut debug_struct = f.debug_struct("");
+		self.collection.debug_fields(&mut debug_struct);
+		debug_struct
+			.field("items", &DebugItems(&self.collection, PhantomData::<R>))
+			.finish()
+	}
+}
+
 impl<R: Role, C: ?Sized> Deref for ItemsPin<R, C> {
 	type Target = C;
 
@@ -291,11 +559,23 @@ impl<R: Role, C: ?Sized> Deref for ItemsPin<R, C> {
 	}
 }
 
-impl<'a, R: Role, C: Items<'a, R> + ?Sized> DerefMut for ItemsPin<R, C>
+impl<R: Role, C: Items<R> + ?Sized> DerefMut for ItemsPin<R, C>
 where
 	C::Item: Unpin,
 {
 	fn deref_mut(&mut self) -> &mut Self::Target {
+		#[cfg(feature = "check-pins")]
+		check_pins::forget(self);
 		&mut self.collection
 	}
 }
+
+/// Serialises the underlying collection transparently.
+///
+/// See [`ItemsPin::deserialize_pinned`](`std_impls::vec`) to restore pinned [`Vec`](`alloc::vec::Vec`)s.
+#[cfg(feature = "serde")]
+impl<R: Role, C: ?Sized + Serialize> Serialize for ItemsPin<R, C> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		self.collection.serialize(serializer)
+	}
+}
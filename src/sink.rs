@@ -0,0 +1,180 @@
+//! Fan-out over pinned [`Sink`]s (with the `"futures"` feature).
+//!
+//! [`Broadcast<S>`] wraps an [`ItemsPin<role::Items, Vec<S>>`](`crate::std_impls::vec`) and is a [`Sink<Item>`] itself
+//! whenever each `S` is one and `Item: Clone`.
+//! Each operation is forwarded to all sinks that haven't failed, in index order:
+//!
+//! - `poll_ready`, `poll_flush` and `poll_close` are pending until every sink is ready, flushed or closed.
+//! - `start_send` clones the item into each sink.
+//!
+//! What happens to sinks that fail is decided by the [`FailurePolicy`].
+
+use crate::{role, ItemsPin, ItemsPinnedMut};
+use alloc::vec::Vec;
+use core::{
+	fmt::{self, Debug, Formatter},
+	pin::Pin,
+	task::{Context, Poll},
+};
+use futures_sink::Sink;
+
+/// How a [`Broadcast<S>`] handles sinks that return an error.
+///
+/// Either way, a sink that failed is not used again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FailurePolicy {
+	/// Failed sinks are dropped in place and the error is discarded.
+	///
+	/// As the sinks are stored in a pinned [`Vec`], this happens as soon as all sinks after it have failed too.
+	/// Until then (or until the [`Broadcast<S>`] is dropped), a failed sink stays in place unused.
+	Drop,
+	/// The error is returned immediately as `(index, error)`, without driving the remaining sinks in that call.
+	Report,
+}
+
+/// A [`Sink`] that forwards each item to all of a pinned [`Vec<S>`] of sinks.
+pub struct Broadcast<S> {
+	sinks: ItemsPin<role::Items, Vec<S>>,
+	policy: FailurePolicy,
+	/// Parallel to `sinks`.
+	failed: Vec<bool>,
+}
+
+/// The sinks are never pinned through the [`Broadcast<S>`] itself.
+impl<S> Unpin for Broadcast<S> {}
+
+impl<S> Broadcast<S> {
+	#[must_use]
+	pub fn new(sinks: ItemsPin<role::Items, Vec<S>>, policy: FailurePolicy) -> Self {
+		Self {
+			sinks,
+			policy,
+			failed: Vec::new(),
+		}
+	}
+
+	#[must_use]
+	pub fn policy(&self) -> FailurePolicy {
+		self.policy
+	}
+
+	#[must_use]
+	pub fn sinks(&self) -> &ItemsPin<role::Items, Vec<S>> {
+		&self.sinks
+	}
+
+	/// Adds a sink via `.push_pinned`.
+	///
+	/// # Errors
+	///
+	/// Iff the underlying [`Vec`] does not have any spare capacity.
+	pub fn push_sink(&mut self, sink: S) -> Result<(), S> {
+		self.sinks.push_pinned(sink)
+	}
+
+	/// Drops the sinks from `len` onwards in place via `.truncate_pinned`, along with their failure state.
+	pub fn truncate_sinks(&mut self, len: usize) {
+		self.sinks.truncate_pinned(len);
+		self.failed.truncate(len);
+	}
+
+	/// Whether the sink at `index` has failed and is no longer used.
+	///
+	/// Sinks that were dropped after failing are out of bounds and not reported here.
+	#[must_use]
+	pub fn is_failed(&self, index: usize) -> bool {
+		self.failed.get(index).copied().unwrap_or(false)
+	}
+
+	#[must_use]
+	pub fn into_inner(self) -> ItemsPin<role::Items, Vec<S>> {
+		self.sinks
+	}
+
+	/// Runs `f` on each sink that hasn't failed, in index order, and applies the [`FailurePolicy`] to errors.
+	fn drive<E>(
+		&mut self,
+		mut f: impl FnMut(Pin<&mut S>) -> Poll<Result<(), E>>,
+	) -> Poll<Result<(), (usize, E)>> {
+		self.failed.resize(self.sinks.len(), false);
+
+		let mut pending = false;
+		for (index, (sink, failed)) in self
+			.sinks
+			.items_pinned_mut()
+			.zip(&mut self.failed)
+			.enumerate()
+		{
+			if *failed {
+				continue;
+			}
+
+			match f(sink) {
+				Poll::Ready(Ok(())) => (),
+				Poll::Ready(Err(error)) => {
+					*failed = true;
+					if self.policy == FailurePolicy::Report {
+						return Poll::Ready(Err((index, error)));
+					}
+				}
+				Poll::Pending => pending = true,
+			}
+		}
+
+		if self.policy == FailurePolicy::Drop {
+			let len = self
+				.failed
+				.iter()
+				.rposition(|failed| !failed)
+				.map_or(0, |i| i + 1);
+			self.sinks.truncate_pinned(len);
+			self.failed.truncate(len);
+		}
+
+		if pending {
+			Poll::Pending
+		} else {
+			Poll::Ready(Ok(()))
+		}
+	}
+}
+
+impl<S, Item: Clone> Sink<Item> for Broadcast<S>
+where
+	S: Sink<Item>,
+{
+	/// The index of the failed sink and its error. Only returned with [`FailurePolicy::Report`].
+	type Error = (usize, S::Error);
+
+	fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.get_mut().drive(|sink| sink.poll_ready(cx))
+	}
+
+	fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), Self::Error> {
+		match self
+			.get_mut()
+			.drive(|sink| Poll::Ready(sink.start_send(item.clone())))
+		{
+			Poll::Ready(result) => result,
+			Poll::Pending => unreachable!(),
+		}
+	}
+
+	fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.get_mut().drive(|sink| sink.poll_flush(cx))
+	}
+
+	fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.get_mut().drive(|sink| sink.poll_close(cx))
+	}
+}
+
+impl<S> Debug for Broadcast<S> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Broadcast")
+			.field("len", &self.sinks.len())
+			.field("policy", &self.policy)
+			.field("failed", &self.failed)
+			.finish()
+	}
+}
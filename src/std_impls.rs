@@ -1,5 +1,12 @@
 //! Item pinning implementations for standard collection types.
 
+#[cfg(feature = "allocator-api2")]
+pub mod allocator_api2;
+#[cfg(feature = "alloc")]
+pub mod boxed;
+#[cfg(feature = "alloc")]
+pub mod linked_list;
 pub mod pin;
 pub mod slice;
+#[cfg(feature = "alloc")]
 pub mod vec;
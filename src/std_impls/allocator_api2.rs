@@ -0,0 +1,231 @@
+//! Pinning implementations for [`allocator_api2`]'s [`Vec<T, A>`] and [`Box<C, A>`] (with the `"allocator-api2"` feature),
+//! so that pinned collections can live in custom allocators (e.g. arenas) on stable Rust.
+//!
+//! [`Vec<T, A>`] implements [`Items<role::Items>`](`Items`) and [`ItemsMut<role::Items>`](`ItemsMut`) like [`alloc::vec::Vec<T>`],
+//! and [`Box<C, A>`] forwards [`Items<R>`](`Items`) and [`ItemsMut<R>`](`ItemsMut`) like [`alloc::boxed::Box<C>`].
+//!
+//! The added [`ItemsPin<role::Items, Vec<T, A>>`](`self`) methods are:
+//!
+//! - `.as_slice`, which narrows [`Vec::as_slice`] to return [`&ItemsPin<role::Items; [T]>`](`super::slice`).
+//! - `.as_mut_slice`, which narrows [`Vec::as_mut_slice`] to return [`&mut ItemsPin<role::Items; [T]>`](`super::slice`).
+//! - `::from_pin_ref` and `::from_pin_mut`, which convert a [`Pin<&Vec<T, A>>`](`Pin`) or [`Pin<&mut Vec<T, A>>`](`Pin`).
+//!   These are `unsafe`, as [`Vec<T, A>`] doesn't pin its items structurally.
+//! - `.leak`, which narrows [`Vec::leak`] to return [`&'a mut ItemsPin<role::Items; [T]>`](`super::slice`).
+//! - `.pop_pinned`, which drops the last value in place if possible, returning [`bool`].
+//! - `.push_pinned`, which allows limited insertions even after pinning.
+//! - `.truncate_pinned`, which forwards [`Vec::truncate`].
+//!
+//! The added [`ItemsPin<R, Box<C, A>>`](`self`) methods are:
+//!
+//! - `.as_deref` and `.as_deref_mut`, which project to the boxed collection as [`&ItemsPin<R, C>`](`ItemsPin`)
+//!   or [`&mut ItemsPin<R, C>`](`ItemsPin`), respectively.
+//!
+//! The added [`ItemsPin<role::Items, Box<[T], A>>`](`self`) and [`ItemsPin<role::Items, Box<Vec<T, A>, B>>`](`self`) functions are:
+//!
+//! - `::from_pin_box`, which converts a [`Pin<Box<[T], A>>`](`Pin`) or [`Pin<Box<Vec<T, A>, B>>`](`Pin`).
+//!   The latter is `unsafe`, as [`Vec<T, A>`] doesn't pin its items structurally.
+//!
+//! These implementations assume that [`allocator_api2`]'s `"nightly"` feature is disabled,
+//! as it turns its types into re-exports of the [`alloc`] ones, which are implemented in [`super::boxed`] and [`super::vec`] already.
+
+use crate::{role, role::Role, Items, ItemsDebug, ItemsMut, ItemsPin};
+use ::allocator_api2::{alloc::Allocator, boxed::Box, vec::Vec};
+use core::{fmt::DebugStruct, pin::Pin, slice};
+
+impl<T, A: Allocator> Items<role::Items> for Vec<T, A> {
+	type Item = T;
+
+	type ItemsIter<'a>
+		= slice::Iter<'a, T>
+	where
+		Self: 'a;
+
+	fn items(&self) -> Self::ItemsIter<'_> {
+		self.iter()
+	}
+}
+
+impl<T, A: Allocator> ItemsDebug<role::Items> for Vec<T, A> {
+	/// Shows `len` and `capacity`, which limit `.push_pinned`.
+	fn debug_fields(&self, debug_struct: &mut DebugStruct) {
+		debug_struct
+			.field("len", &self.len())
+			.field("capacity", &self.capacity());
+	}
+}
+
+impl<T, A: Allocator> ItemsMut<role::Items> for Vec<T, A> {
+	type ItemsMutIter<'a>
+		= slice::IterMut<'a, T>
+	where
+		Self: 'a;
+
+	fn items_mut(&mut self) -> Self::ItemsMutIter<'_> {
+		self.iter_mut()
+	}
+}
+
+impl<T, A: Allocator> ItemsPin<role::Items, Vec<T, A>> {
+	/// Pins `vec`'s items.
+	///
+	/// Like [`Box::pin_in`], this requires `A: 'static`, as an allocator that borrows its memory
+	/// could let it go out of scope without the pinned items being dropped (e.g. after [`mem::forget`](`core::mem::forget`)):
+	///
+	/// ```compile_fail
+	/// use allocator_api2::{alloc::Global, vec::Vec};
+	/// use napje::{role, ItemsPin};
+	///
+	/// let allocator = Global;
+	/// let _ = ItemsPin::<role::Items, Vec<u8, &Global>>::pin(Vec::new_in(&allocator));
+	/// ```
+	#[must_use]
+	pub fn pin(vec: Vec<T, A>) -> Self
+	where
+		A: 'static,
+	{
+		// SAFETY: The vector is owned, so its items can only be moved through `ItemsPin`'s API from here on.
+		// With `A: 'static`, their memory can't be freed while they are pinned, except by dropping the vector.
+		unsafe { ItemsPin::new_unchecked(vec) }
+	}
+
+	/// # Safety
+	///
+	/// [`Vec<T, A>`] doesn't pin its items structurally, so pinning it doesn't guarantee that its items stay in place.
+	/// No code may move the items (e.g. by reallocating the vector through [`Pin::get_unchecked_mut`])
+	/// from when they were pinned until they are dropped.
+	#[must_use]
+	pub unsafe fn from_pin_ref(vec: Pin<&Vec<T, A>>) -> &Self {
+		ItemsPin::new_ref_unchecked(Pin::get_ref(vec))
+	}
+
+	/// # Safety
+	///
+	/// See `::from_pin_ref`.
+	#[must_use]
+	pub unsafe fn from_pin_mut(vec: Pin<&mut Vec<T, A>>) -> &mut Self {
+		ItemsPin::new_mut_unchecked(Pin::get_unchecked_mut(vec))
+	}
+
+	/// # Errors
+	///
+	/// Iff the underlying [`Vec`] does not have any spare capacity.
+	pub fn push_pinned(&mut self, value: T) -> Result<(), T> {
+		if self.collection.len() < self.collection.capacity() {
+			self.collection.push(value);
+			Ok(())
+		} else {
+			Err(value)
+		}
+	}
+
+	pub fn pop_pinned(&mut self) -> bool {
+		!self.collection.is_empty() && {
+			self.truncate_pinned(self.collection.len() - 1);
+			true
+		}
+	}
+
+	pub fn truncate_pinned(&mut self, len: usize) {
+		#[cfg(feature = "check-pins")]
+		self.check_pins();
+		self.collection.truncate(len);
+		#[cfg(feature = "check-pins")]
+		self.record_pins();
+	}
+
+	#[must_use]
+	pub fn as_slice(&self) -> &ItemsPin<role::Items, [T]> {
+		unsafe { &*(self.collection.as_slice() as *const _ as *const _) }
+	}
+
+	#[must_use]
+	pub fn as_mut_slice(&mut self) -> &mut ItemsPin<role::Items, [T]> {
+		unsafe { &mut *(self.collection.as_mut_slice() as *mut _ as *mut _) }
+	}
+
+	/// Requires `A: 'static` like `::pin`, as the items must never be freed without being dropped.
+	#[must_use]
+	pub fn leak<'a>(self) -> &'a mut ItemsPin<role::Items, [T]>
+	where
+		A: 'static,
+	{
+		// SAFETY: `ItemsPin` is `#[repr(transparent)]`, and with `A: 'static` the leaked buffer stays allocated forever.
+		unsafe { &mut *(self.into_collection().leak() as *mut _ as *mut _) }
+	}
+}
+
+impl<R: Role, C: ?Sized, A: Allocator> Items<R> for Box<C, A>
+where
+	C: Items<R>,
+{
+	type Item = C::Item;
+	type ItemsIter<'a>
+		= C::ItemsIter<'a>
+	where
+		Self: 'a,
+		Self::Item: 'a;
+
+	fn items(&self) -> Self::ItemsIter<'_> {
+		(**self).items()
+	}
+}
+
+impl<R: Role, C: ?Sized, A: Allocator> ItemsDebug<R> for Box<C, A>
+where
+	C: ItemsDebug<R>,
+{
+	fn debug_fields(&self, debug_struct: &mut DebugStruct) {
+		(**self).debug_fields(debug_struct)
+	}
+}
+
+impl<R: Role, C: ?Sized, A: Allocator> ItemsMut<R> for Box<C, A>
+where
+	C: ItemsMut<R>,
+{
+	type ItemsMutIter<'a>
+		= C::ItemsMutIter<'a>
+	where
+		Self: 'a,
+		Self::Item: 'a;
+
+	fn items_mut(&mut self) -> Self::ItemsMutIter<'_> {
+		(**self).items_mut()
+	}
+}
+
+impl<R: Role, C: ?Sized, A: Allocator> ItemsPin<R, Box<C, A>>
+where
+	C: Items<R>,
+{
+	#[must_use]
+	pub fn as_deref(&self) -> &ItemsPin<R, C> {
+		let collection: &C = &self.collection;
+		unsafe { &*(collection as *const C as *const _) }
+	}
+
+	#[must_use]
+	pub fn as_deref_mut(&mut self) -> &mut ItemsPin<R, C> {
+		let collection: &mut C = &mut self.collection;
+		unsafe { &mut *(collection as *mut C as *mut _) }
+	}
+}
+
+impl<T, A: Allocator> ItemsPin<role::Items, Box<[T], A>> {
+	#[must_use]
+	pub fn from_pin_box(slice: Pin<Box<[T], A>>) -> Self {
+		unsafe { ItemsPin::new_unchecked(Pin::into_inner_unchecked(slice)) }
+	}
+}
+
+impl<T, A: Allocator, B: Allocator> ItemsPin<role::Items, Box<Vec<T, A>, B>> {
+	/// # Safety
+	///
+	/// [`Vec<T, A>`] doesn't pin its items structurally, so pinning it doesn't guarantee that its items stay in place.
+	/// No code may have moved the items (e.g. by reallocating the vector through [`Pin::get_unchecked_mut`])
+	/// since they were pinned.
+	#[must_use]
+	pub unsafe fn from_pin_box(vec: Pin<Box<Vec<T, A>, B>>) -> Self {
+		ItemsPin::new_unchecked(Pin::into_inner_unchecked(vec))
+	}
+}
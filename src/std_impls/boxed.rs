@@ -0,0 +1,95 @@
+//! Forwarding implementation for [`Box<C>`] where [`C: Items<R>`](`Items`) or [`C: ItemsMut<R>`](`ItemsMut`)
+//! where `R` is **any** [`Role`].
+//!
+//! The added [`ItemsPin<R, Box<C>>`](`self`) methods are:
+//!
+//! - `.as_deref` and `.as_deref_mut`, which project to the boxed collection as [`&ItemsPin<R, C>`](`ItemsPin`)
+//!   or [`&mut ItemsPin<R, C>`](`ItemsPin`), respectively.
+//!
+//! The added [`ItemsPin<role::Items, Box<[T]>>`](`self`) and [`ItemsPin<role::Items, Box<Vec<T>>>`](`self`) functions are:
+//!
+//! - `::from_pin_box`, which converts a [`Pin<Box<[T]>>`](`Pin`) or [`Pin<Box<Vec<T>>>`](`Pin`).
+//!   The latter is `unsafe`, as [`Vec<T>`] doesn't pin its items structurally.
+
+use crate::{role, role::Role, Items, ItemsDebug, ItemsMut, ItemsPin};
+use alloc::{boxed::Box, vec::Vec};
+use core::{fmt::DebugStruct, pin::Pin};
+
+impl<R: Role, C: ?Sized> Items<R> for Box<C>
+where
+	C: Items<R>,
+{
+	type Item = C::Item;
+	type ItemsIter<'a>
+		= C::ItemsIter<'a>
+	where
+		Self: 'a,
+		Self::Item: 'a;
+
+	fn items(&self) -> Self::ItemsIter<'_> {
+		(**self).items()
+	}
+}
+
+impl<R: Role, C: ?Sized> ItemsDebug<R> for Box<C>
+where
+	C: ItemsDebug<R>,
+{
+	fn debug_fields(&self, debug_struct: &mut DebugStruct) {
+		(**self).debug_fields(debug_struct)
+	}
+}
+
+impl<R: Role, C: ?Sized> ItemsMut<R> for Box<C>
+where
+	C: ItemsMut<R>,
+{
+	type ItemsMutIter<'a>
+		= C::ItemsMutIter<'a>
+	where
+		Self: 'a,
+		Self::Item: 'a;
+
+	fn items_mut(&mut self) -> Self::ItemsMutIter<'_> {
+		(**self).items_mut()
+	}
+}
+
+impl<R: Role, C: ?Sized> ItemsPin<R, Box<C>>
+where
+	C: Items<R>,
+{
+	#[must_use]
+	pub fn as_deref(&self) -> &ItemsPin<R, C> {
+		let collection: &C = &self.collection;
+		// SAFETY: `ItemsPin` is `#[repr(transparent)]`, and the boxed collection's items are the pinned ones.
+		unsafe { &*(collection as *const C as *const _) }
+	}
+
+	#[must_use]
+	pub fn as_deref_mut(&mut self) -> &mut ItemsPin<R, C> {
+		let collection: &mut C = &mut self.collection;
+		// SAFETY: As above. The `Box` can't be replaced while the result borrows it.
+		unsafe { &mut *(collection as *mut C as *mut _) }
+	}
+}
+
+impl<T> ItemsPin<role::Items, Box<[T]>> {
+	#[must_use]
+	pub fn from_pin_box(slice: Pin<Box<[T]>>) -> Self {
+		// SAFETY: The items are part of the pinned boxed slice itself, so they are pinned already.
+		unsafe { ItemsPin::new_unchecked(Pin::into_inner_unchecked(slice)) }
+	}
+}
+
+impl<T> ItemsPin<role::Items, Box<Vec<T>>> {
+	/// # Safety
+	///
+	/// [`Vec<T>`] doesn't pin its items structurally, so pinning it doesn't guarantee that its items stay in place.
+	/// No code may have moved the items (e.g. by reallocating the vector through [`Pin::get_unchecked_mut`])
+	/// since they were pinned.
+	#[must_use]
+	pub unsafe fn from_pin_box(vec: Pin<Box<Vec<T>>>) -> Self {
+		ItemsPin::new_unchecked(Pin::into_inner_unchecked(vec))
+	}
+}
@@ -0,0 +1,194 @@
+//! Pinning implementation for [`LinkedList<T>`] with [`role::Items`].
+//!
+//! Each item of a [`LinkedList<T>`] lives in its own heap node, which is relinked but never moved.
+//! Unlike [`ItemsPin<role::Items, Vec<T>>`](`super::vec`), a pinned list can therefore grow without limit.
+//!
+//! The added [`ItemsPin<role::Items, LinkedList<T>>`](`self`) methods are:
+//!
+//! - `.cursor_front_pinned_mut`, which returns a [`CursorPinnedMut`] for inserting and removing items anywhere in the list.
+//! - `.pop_back_pinned` and `.pop_front_pinned`, which drop the respective item in place if possible, returning [`bool`].
+//! - `.push_back_pinned` and `.push_front_pinned`, which always succeed.
+//!
+//! Removal relies on [`LinkedList<T>`] dropping its items inside their nodes,
+//! by splitting the item off into a separate list and dropping that.
+
+#![allow(clippy::linkedlist)] // Supporting it is the point of this module.
+
+use crate::{role, Items, ItemsDebug, ItemsMut, ItemsPin};
+use alloc::collections::linked_list::{Iter, IterMut, LinkedList};
+use core::{fmt::DebugStruct, pin::Pin};
+
+impl<T> Items<role::Items> for LinkedList<T> {
+	type Item = T;
+
+	type ItemsIter<'a>
+		= Iter<'a, T>
+	where
+		Self: 'a;
+
+	fn items(&self) -> Self::ItemsIter<'_> {
+		self.iter()
+	}
+}
+
+impl<T> ItemsDebug<role::Items> for LinkedList<T> {
+	/// Shows `len`.
+	fn debug_fields(&self, debug_struct: &mut DebugStruct) {
+		debug_struct.field("len", &self.len());
+	}
+}
+
+impl<T> ItemsMut<role::Items> for LinkedList<T> {
+	type ItemsMutIter<'a>
+		= IterMut<'a, T>
+	where
+		Self: 'a;
+
+	fn items_mut(&mut self) -> Self::ItemsMutIter<'_> {
+		self.iter_mut()
+	}
+}
+
+/// Drops the item at `index` in place, without moving any others.
+///
+/// Does nothing iff `index` is out of bounds.
+fn drop_at_in_place<T>(list: &mut LinkedList<T>, index: usize) -> bool {
+	if index >= list.len() {
+		return false;
+	}
+
+	let mut tail = list.split_off(index);
+	let mut rest = tail.split_off(1);
+	list.append(&mut rest);
+	// Dropping the list drops the item inside its node.
+	drop(tail);
+	true
+}
+
+/// Links `value` in at `index`, without moving any other items.
+fn insert_at<T>(list: &mut LinkedList<T>, index: usize, value: T) {
+	let mut tail = list.split_off(index);
+	tail.push_front(value);
+	list.append(&mut tail);
+}
+
+impl<T> ItemsPin<role::Items, LinkedList<T>> {
+	#[must_use]
+	pub fn pin(list: LinkedList<T>) -> Self {
+		// SAFETY: Each item lives in its own heap node, which isn't moved by relinking, splitting or appending.
+		// This API only drops items in place.
+		unsafe { ItemsPin::new_unchecked(list) }
+	}
+
+	pub fn push_back_pinned(&mut self, value: T) {
+		self.collection.push_back(value)
+	}
+
+	pub fn push_front_pinned(&mut self, value: T) {
+		self.collection.push_front(value)
+	}
+
+	pub fn pop_back_pinned(&mut self) -> bool {
+		let len = self.collection.len();
+		!self.collection.is_empty() && self.drop_at_pinned(len - 1)
+	}
+
+	pub fn pop_front_pinned(&mut self) -> bool {
+		self.drop_at_pinned(0)
+	}
+
+	fn drop_at_pinned(&mut self, index: usize) -> bool {
+		#[cfg(feature = "check-pins")]
+		self.check_pins();
+		let dropped = drop_at_in_place(&mut self.collection, index);
+		#[cfg(feature = "check-pins")]
+		self.record_pins();
+		dropped
+	}
+
+	/// Returns a cursor that starts at the front item (or the "ghost" position, if the list is empty).
+	#[must_use]
+	pub fn cursor_front_pinned_mut(&mut self) -> CursorPinnedMut<'_, T> {
+		CursorPinnedMut {
+			list: self,
+			index: 0,
+		}
+	}
+}
+
+/// A cursor over a pinned [`LinkedList<T>`] that can insert and remove items anywhere.
+///
+/// Like the standard library's (unstable) cursors, this has a "ghost" position past the back of the list,
+/// from which moving forwards wraps around to the front and vice versa.
+///
+/// As the stable [`LinkedList<T>`] API doesn't expose its nodes,
+/// each operation except for moving takes time linear in the distance from the nearer end of the list.
+pub struct CursorPinnedMut<'a, T> {
+	list: &'a mut ItemsPin<role::Items, LinkedList<T>>,
+	/// `list.len()` at the ghost position.
+	index: usize,
+}
+
+impl<T> CursorPinnedMut<'_, T> {
+	/// The index of the current item, or [`None`] at the ghost position.
+	#[must_use]
+	pub fn index(&self) -> Option<usize> {
+		(self.index < self.list.collection.len()).then_some(self.index)
+	}
+
+	pub fn move_next(&mut self) {
+		self.index = if self.index < self.list.collection.len() {
+			self.index + 1
+		} else {
+			0
+		};
+	}
+
+	pub fn move_prev(&mut self) {
+		self.index = if self.index > 0 {
+			self.index - 1
+		} else {
+			self.list.collection.len()
+		};
+	}
+
+	#[must_use]
+	pub fn current(&mut self) -> Option<Pin<&mut T>> {
+		let len = self.list.collection.len();
+		let item = if self.index < len / 2 {
+			self.list.collection.iter_mut().nth(self.index)
+		} else {
+			self.list
+				.collection
+				.iter_mut()
+				.rev()
+				.nth(len.checked_sub(self.index + 1)?)
+		};
+		// SAFETY: The list is pinned, and the item stays in its node until dropped in place.
+		item.map(|item| unsafe { Pin::new_unchecked(item) })
+	}
+
+	/// Inserts `value` after the current item (or at the front, at the ghost position).
+	pub fn insert_after(&mut self, value: T) {
+		let len = self.list.collection.len();
+		if self.index < len {
+			insert_at(&mut self.list.collection, self.index + 1, value);
+		} else {
+			self.list.collection.push_front(value);
+			self.index += 1;
+		}
+	}
+
+	/// Inserts `value` before the current item (or at the back, at the ghost position).
+	pub fn insert_before(&mut self, value: T) {
+		insert_at(&mut self.list.collection, self.index, value);
+		self.index += 1;
+	}
+
+	/// Drops the current item in place and moves to the next one.
+	///
+	/// Does nothing and returns `false` at the ghost position.
+	pub fn remove_current_pinned(&mut self) -> bool {
+		self.list.drop_at_pinned(self.index)
+	}
+}
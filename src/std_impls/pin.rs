@@ -2,52 +2,95 @@
 //! where [`C: Items<R>`](`Items`) or [`C: ItemsMut<R>`](`ItemsMut`)
 //! where `R` is **any** [`Role`].
 //!
-//! This doesn't add any special methods
-//! (and you'll have to implement pinning and unpinning yourself),
-//! but it gives you some auxiliary implementation to work with.
+//! This doesn't add any special methods, but it gives you some auxiliary implementation to work with.
+//!
+//! To use the pinned-collection methods of this crate with a [`Pin`] you received from elsewhere,
+//! convert it into an [`ItemsPin`](`crate::ItemsPin`) instead:
+//!
+//! - [`Pin<&[T]>`] and [`Pin<&mut [T]>`] via `ItemsPin::<role::Items, [T]>::from_pin_ref` and `::from_pin_mut`,
+//!   [documented here](`super::slice`).
+//! - [`Pin<&Vec<T>>`] and [`Pin<&mut Vec<T>>`] via the `unsafe` `ItemsPin::<role::Items, Vec<T>>::from_pin_ref` and `::from_pin_mut`,
+//!   [documented here](`super::vec`) (with the `"alloc"` feature).
+//! - [`Pin<Box<[T]>>`] and (`unsafe`ly) [`Pin<Box<Vec<T>>>`] via `ItemsPin::<role::Items, Box<_>>::from_pin_box`,
+//!   [documented here](`super::boxed`) (with the `"alloc"` feature).
 
-use crate::{role::Role, Items, ItemsMut, ItemsPinned, ItemsPinnedMut, PinIter};
-use core::pin::Pin;
+use crate::{role::Role, Items, ItemsDebug, ItemsMut, ItemsPinned, ItemsPinnedMut, PinIter};
+use core::{fmt::DebugStruct, pin::Pin};
 
-impl<'a, R: Role, C> Items<'a, R> for Pin<C>
+impl<R: Role, C> Items<R> for Pin<C>
 where
-	C: Items<'a, R>,
+	C: Items<R>,
 {
 	type Item = C::Item;
-	type ItemsIter = C::ItemsIter;
+	type ItemsIter<'a>
+		= C::ItemsIter<'a>
+	where
+		Self: 'a,
+		Self::Item: 'a;
 
-	fn items(&'a self) -> Self::ItemsIter {
+	fn items(&self) -> Self::ItemsIter<'_> {
 		unsafe { &*(self as *const Pin<C>).cast::<C>() }.items()
 	}
 }
-impl<'a, R: Role, C> ItemsPinned<'a, R> for Pin<C>
+
+impl<R: Role, C> ItemsDebug<R> for Pin<C>
+where
+	C: ItemsDebug<R>,
+{
+	fn debug_fields(&self, debug_struct: &mut DebugStruct) {
+		unsafe { &*(self as *const Pin<C>).cast::<C>() }.debug_fields(debug_struct)
+	}
+}
+impl<R: Role, C> ItemsPinned<R> for Pin<C>
 where
-	C: Items<'a, R>,
+	C: Items<R>,
 {
-	type ItemsPinnedIter = PinIter<C::ItemsIter>;
+	type ItemsPinnedIter<'a>
+		= PinIter<C::ItemsIter<'a>>
+	where
+		Self: 'a,
+		Self::Item: 'a;
 
-	fn items_pinned(&'a self) -> Self::ItemsPinnedIter {
+	fn items_pinned(&self) -> Self::ItemsPinnedIter<'_> {
 		unsafe { PinIter::new_unchecked(self.items()) }
 	}
 }
 
-impl<'a, R: Role, C> ItemsMut<'a, R> for Pin<C>
+/// Only for [`Unpin`] items, as [`Pin<C>`] pins them:
+///
+/// ```compile_fail
+/// use napje::ItemsMut;
+/// use std::marker::PhantomPinned;
+///
+/// let mut pinned = Box::into_pin(vec![PhantomPinned, PhantomPinned].into_boxed_slice());
+/// let _ = pinned.items_mut();
+/// ```
+impl<R: Role, C> ItemsMut<R> for Pin<C>
 where
-	C: ItemsMut<'a, R>,
+	C: ItemsMut<R>,
+	C::Item: Unpin,
 {
-	type ItemsMutIter = C::ItemsMutIter;
+	type ItemsMutIter<'a>
+		= C::ItemsMutIter<'a>
+	where
+		Self: 'a,
+		Self::Item: 'a;
 
-	fn items_mut(&'a mut self) -> Self::ItemsMutIter {
+	fn items_mut(&mut self) -> Self::ItemsMutIter<'_> {
 		unsafe { &mut *(self as *mut Pin<C>).cast::<C>() }.items_mut()
 	}
 }
-impl<'a, R: Role, C> ItemsPinnedMut<'a, R> for Pin<C>
+impl<R: Role, C> ItemsPinnedMut<R> for Pin<C>
 where
-	C: ItemsMut<'a, R>,
+	C: ItemsMut<R>,
 {
-	type ItemsPinnedMutIter = PinIter<C::ItemsMutIter>;
+	type ItemsPinnedMutIter<'a>
+		= PinIter<C::ItemsMutIter<'a>>
+	where
+		Self: 'a,
+		Self::Item: 'a;
 
-	fn items_pinned_mut(&'a mut self) -> Self::ItemsPinnedMutIter {
-		unsafe { PinIter::new_unchecked(self.items_mut()) }
+	fn items_pinned_mut(&mut self) -> Self::ItemsPinnedMutIter<'_> {
+		unsafe { PinIter::new_unchecked((*(self as *mut Pin<C>).cast::<C>()).items_mut()) }
 	}
 }
@@ -2,26 +2,59 @@
 //!
 //! The added [`ItemsPin<role::Items, [T]>`](`self`) methods are:
 //!
-//! - TODO
+//! - `::from_pin_ref` and `::from_pin_mut`, which convert a [`Pin<&[T]>`] or [`Pin<&mut [T]>`],
+//!   as pinning a slice pins its items.
+//! - `.par_items_pinned` and `.par_items_pinned_mut` (with the `"rayon"` feature),
+//!   which return `PinParIter`s over the items.
+//! - `.race_pinned`, which polls the futures in place until one completes, resolving to its index and output.
+//! - `.try_join_all_pinned` (with the `"alloc"` feature), which polls the futures in place until all succeed or one fails,
+//!   resolving to their outputs or to the failing index and error.
+//!
+//! Unlike their [`Vec<F>`](`super::vec`) counterparts, these leave all futures in place once resolved,
+//! as a slice borrowed for less than `'static` can't drop its items itself.
+//! The futures are dropped along with the slice instead. See [`crate::future`] for details.
+//!
+//! Completed futures are polled again if another race or join is started on the same slice,
+//! which many futures don't allow (they may panic).
 
-use crate::{role, Items, ItemsMut, ItemsPin};
-use core::slice::{Iter, IterMut};
-use std::{mem::ManuallyDrop, ops::Range};
+#[cfg(feature = "alloc")]
+use crate::future::TryJoinAll;
+use crate::{future::Race, role, Items, ItemsDebug, ItemsMut, ItemsPin};
+use core::{
+	future::Future,
+	mem::ManuallyDrop,
+	ops::Range,
+	pin::Pin,
+	slice::{Iter, IterMut},
+};
 
-impl<'a, T: 'a> Items<'a, role::Items> for [T] {
+#[cfg(feature = "rayon")]
+use crate::PinParIter;
+#[cfg(feature = "rayon")]
+use rayon::iter::{IntoParallelRefIterator, IntoParallelRefMutIterator};
+
+impl<T> Items<role::Items> for [T] {
 	type Item = T;
 
-	type ItemsIter = Iter<'a, T>;
+	type ItemsIter<'a>
+		= Iter<'a, T>
+	where
+		Self: 'a;
 
-	fn items(&'a self) -> Self::ItemsIter {
+	fn items(&self) -> Self::ItemsIter<'_> {
 		self.iter()
 	}
 }
 
-impl<'a, T: 'a> ItemsMut<'a, role::Items> for [T] {
-	type ItemsMutIter = IterMut<'a, T>;
+impl<T> ItemsDebug<role::Items> for [T] {}
+
+impl<T> ItemsMut<role::Items> for [T] {
+	type ItemsMutIter<'a>
+		= IterMut<'a, T>
+	where
+		Self: 'a;
 
-	fn items_mut(&'a mut self) -> Self::ItemsMutIter {
+	fn items_mut(&mut self) -> Self::ItemsMutIter<'_> {
 		self.iter_mut()
 	}
 }
@@ -32,15 +65,42 @@ impl<T> ItemsPin<role::Items, [T]> {
 		unsafe { &mut *(slice as *mut _ as *mut _) }
 	}
 
+	#[must_use]
+	pub fn from_pin_ref(slice: Pin<&[T]>) -> &Self {
+		// SAFETY: `ItemsPin` is `#[repr(transparent)]`. The items are part of the pinned slice itself,
+		// so they are pinned already.
+		unsafe { &*(Pin::get_ref(slice) as *const _ as *const _) }
+	}
+
+	/// Like [`Pin<&mut [T]>`], the result only hands out `&mut T` if `T: Unpin`:
+	///
+	/// ```compile_fail
+	/// use napje::{role, ItemsMut, ItemsPin};
+	/// use std::{marker::PhantomPinned, mem};
+	///
+	/// let mut pinned = Box::pin([PhantomPinned, PhantomPinned]);
+	/// let slice = ItemsPin::<role::Items, [_]>::from_pin_mut(pinned.as_mut());
+	/// let mut items = slice.items_mut();
+	/// let (a, b) = (items.next().unwrap(), items.next().unwrap());
+	/// mem::swap(a, b);
+	/// ```
+	#[must_use]
+	pub fn from_pin_mut(slice: Pin<&mut [T]>) -> &mut Self {
+		// SAFETY: `ItemsPin` is `#[repr(transparent)]`. The items are part of the pinned slice itself,
+		// and `&mut ItemsPin<role::Items, [T]>` only gives out `&mut T` (via `ItemsMut`/`DerefMut`) if `T: Unpin`.
+		unsafe { &mut *(Pin::get_unchecked_mut(slice) as *mut _ as *mut _) }
+	}
+
 	/// Drops all items outside `range` in place and returns the remaining subslice.
 	///
 	/// # Panics
 	///
 	/// Iff `range.start > range.end || range.end > self.len()`.
 	pub fn truncate(&'static mut self, range: Range<usize>) -> &'static mut Self {
-		if range.start > range.end || range.end > self.len() {
-			panic!("Invalid range.")
-		}
+		assert!(
+			range.start <= range.end && range.end <= self.len(),
+			"Invalid range."
+		);
 
 		let (a, bc) = self.collection.split_at_mut(range.start);
 		let (b, c) = bc.split_at_mut(range.len());
@@ -57,3 +117,47 @@ impl<T> ItemsPin<role::Items, [T]> {
 		}
 	}
 }
+
+impl<F: Future> ItemsPin<role::Items, [F]> {
+	/// Resolves to the index and output of the first future to complete. All futures stay in place.
+	///
+	/// The winner (and any futures that completed in earlier races) stays in place in its completed state,
+	/// so starting another race on this slice polls it again after it returned [`Poll::Ready`](`core::task::Poll::Ready`),
+	/// which many futures don't allow.
+	/// The returned [`Race`] itself panics if it's polled again after it resolved.
+	pub fn race_pinned(&mut self) -> Race<'_, [F]> {
+		Race::new(self, |_| ())
+	}
+}
+
+#[cfg(feature = "alloc")]
+impl<F: Future<Output = Result<T, E>>, T, E> ItemsPin<role::Items, [F]> {
+	/// Resolves to all outputs in index order, or to the index and error of the first future to fail.
+	/// All futures stay in place.
+	///
+	/// Completed futures stay in place in their completed state,
+	/// so starting another join or race on this slice polls them again after they returned [`Poll::Ready`](`core::task::Poll::Ready`),
+	/// which many futures don't allow.
+	/// The returned [`TryJoinAll`] itself panics if it's polled again after it resolved.
+	pub fn try_join_all_pinned(&mut self) -> TryJoinAll<'_, [F], T> {
+		TryJoinAll::new(self, |_| ())
+	}
+}
+
+#[cfg(feature = "rayon")]
+impl<T> ItemsPin<role::Items, [T]> {
+	#[must_use]
+	pub fn par_items_pinned(&self) -> PinParIter<rayon::slice::Iter<'_, T>>
+	where
+		T: Sync,
+	{
+		unsafe { PinParIter::new_unchecked(self.collection.par_iter()) }
+	}
+
+	pub fn par_items_pinned_mut(&mut self) -> PinParIter<rayon::slice::IterMut<'_, T>>
+	where
+		T: Send,
+	{
+		unsafe { PinParIter::new_unchecked(self.collection.par_iter_mut()) }
+	}
+}
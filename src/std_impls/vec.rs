@@ -3,29 +3,100 @@
 //! The added [`ItemsPin<role::Items, Vec<T>>`](`self`) methods are:
 //!
 //! - `.as_slice`, which narrows [`Vec::as_slice`] to return [`&ItemsPin<role::Items; [T]>`](`super::slice`).
-//! - `.as_slice_mut`, which narrows [`Vec::as_slice_mut`] to return [`&mut ItemsPin<role::Items; [T]>`](`super::slice`).
+//! - `.as_mut_slice`, which narrows [`Vec::as_mut_slice`] to return [`&mut ItemsPin<role::Items; [T]>`](`super::slice`).
+//! - `.close_all_pinned` (for items that implement [`PollClose`]), which closes all items concurrently
+//!   and drops the closed items at the end of the vector in place right away, in index order.
+//! - `.deserialize_pinned` and `.deserialize_pinned_with_reserve` (with the `"serde"` feature),
+//!   which restore a [`Vec<T>`] and pin it in one step.
+//! - `::from_pin_ref` and `::from_pin_mut`, which convert a [`Pin<&Vec<T>>`](`core::pin::Pin`) or [`Pin<&mut Vec<T>>`](`core::pin::Pin`).
+//!   These are `unsafe`, as [`Vec<T>`] doesn't pin its items structurally.
 //! - `.leak`, which narrows [`Vec::leak`] to return [`&'static mut ItemsPin<role::Items; [T]>`](`super::slice`).
+//! - `.par_items_pinned` and `.par_items_pinned_mut` (with the `"rayon"` feature),
+//!   which forward to the respective [`ItemsPin<role::Items; [T]>`](`super::slice`) methods.
 //! - `.pop_pinned`, which drops the last value in place if possible, returning [`bool`].
 //! - `.push_pinned`, which allows limited insertions even after pinning.
+//! - `.race_pinned`, which polls the futures in place until one completes, resolving to its index and output.
 //! - `.truncate_pinned`, which forwards [`Vec::truncate`].
+//! - `.try_join_all_pinned`, which polls the futures in place until all succeed or one fails,
+//!   resolving to their outputs or to the failing index and error.
+//!
+//! Both future combinators clear the vector once resolved, dropping all futures in place in index order.
+//! See [`crate::future`] for details.
+//!
+//! For nested vectors, the added [`ItemsPin<role::Items, Vec<Vec<T>>>`](`self`) methods are:
+//!
+//! - `.items_pin_nested_mut`, which projects to each inner vector as [`&mut ItemsPin<role::Items, Vec<T>>`](`self`)
+//!   (rather than [`Pin<&mut Vec<T>>`](`core::pin::Pin`)), so that they can keep using e.g. `.push_pinned`.
+//! - `.items_pinned_flat_mut`, which iterates over the items of all inner vectors as [`Pin<&mut T>`](`core::pin::Pin`).
+//!
+//! The inner vectors can't be replaced through either projection, so their items stay in place:
+//!
+//! ```compile_fail
+//! use napje::{role, ItemsPin};
+//! use std::{marker::PhantomPinned, mem};
+//!
+//! let mut vecs = ItemsPin::<role::Items, Vec<Vec<_>>>::pin(vec![vec![PhantomPinned]]);
+//! let inner = vecs.items_pin_nested_mut().next().unwrap();
+//! mem::swap(inner, &mut Vec::new()); // A `&mut ItemsPin<role::Items, Vec<T>>` isn't a `&mut Vec<T>`.
+//! ```
+//!
+//! ```compile_fail
+//! use napje::{role, ItemsPin, ItemsPinnedMut};
+//! use std::{marker::PhantomPinned, mem};
+//!
+//! let mut vecs = ItemsPin::<role::Items, Vec<Vec<_>>>::pin(vec![vec![PhantomPinned]]);
+//! let mut inner = vecs.items_pinned_mut().next().unwrap();
+//! mem::swap(&mut *inner, &mut Vec::new()); // `Vec<PhantomPinned>` isn't `Unpin`.
+//! ```
 
-use crate::{role, Items, ItemsMut, ItemsPin};
-use std::slice;
+use crate::{
+	future::{PollClose, Race, TryJoinAll},
+	role, Items, ItemsDebug, ItemsMut, ItemsPin, ItemsPinnedMut, PinIter,
+};
+use alloc::vec::Vec;
+use core::{
+	fmt::DebugStruct,
+	future::Future,
+	iter::FlatMap,
+	pin::Pin,
+	slice,
+	task::{Context, Poll},
+};
 
-impl<'a, T: 'a> Items<'a, role::Items> for Vec<T> {
+#[cfg(feature = "rayon")]
+use crate::PinParIter;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer};
+
+impl<T> Items<role::Items> for Vec<T> {
 	type Item = T;
 
-	type ItemsIter = slice::Iter<'a, T>;
+	type ItemsIter<'a>
+		= slice::Iter<'a, T>
+	where
+		Self: 'a;
 
-	fn items(&'a self) -> Self::ItemsIter {
+	fn items(&self) -> Self::ItemsIter<'_> {
 		self.iter()
 	}
 }
 
-impl<'a, T: 'a> ItemsMut<'a, role::Items> for Vec<T> {
-	type ItemsMutIter = slice::IterMut<'a, T>;
+impl<T> ItemsDebug<role::Items> for Vec<T> {
+	/// Shows `len` and `capacity`, which limit `.push_pinned`.
+	fn debug_fields(&self, debug_struct: &mut DebugStruct) {
+		debug_struct
+			.field("len", &self.len())
+			.field("capacity", &self.capacity());
+	}
+}
+
+impl<T> ItemsMut<role::Items> for Vec<T> {
+	type ItemsMutIter<'a>
+		= slice::IterMut<'a, T>
+	where
+		Self: 'a;
 
-	fn items_mut(&'a mut self) -> Self::ItemsMutIter {
+	fn items_mut(&mut self) -> Self::ItemsMutIter<'_> {
 		self.iter_mut()
 	}
 }
@@ -36,6 +107,24 @@ impl<T> ItemsPin<role::Items, Vec<T>> {
 		unsafe { ItemsPin::new_unchecked(vec) }
 	}
 
+	/// # Safety
+	///
+	/// [`Vec<T>`] doesn't pin its items structurally, so pinning it doesn't guarantee that its items stay in place.
+	/// No code may move the items (e.g. by reallocating the vector through [`Pin::get_unchecked_mut`])
+	/// from when they were pinned until they are dropped.
+	#[must_use]
+	pub unsafe fn from_pin_ref(vec: Pin<&Vec<T>>) -> &Self {
+		ItemsPin::new_ref_unchecked(Pin::get_ref(vec))
+	}
+
+	/// # Safety
+	///
+	/// See `::from_pin_ref`.
+	#[must_use]
+	pub unsafe fn from_pin_mut(vec: Pin<&mut Vec<T>>) -> &mut Self {
+		ItemsPin::new_mut_unchecked(Pin::get_unchecked_mut(vec))
+	}
+
 	/// # Errors
 	///
 	/// Iff the underlying [`Vec`] does not have any spare capacity.
@@ -50,27 +139,206 @@ impl<T> ItemsPin<role::Items, Vec<T>> {
 
 	pub fn pop_pinned(&mut self) -> bool {
 		!self.collection.is_empty() && {
-			self.collection.truncate(self.collection.len() - 1);
+			self.truncate_pinned(self.collection.len() - 1);
 			true
 		}
 	}
 
 	pub fn truncate_pinned(&mut self, len: usize) {
-		self.collection.truncate(len)
+		#[cfg(feature = "check-pins")]
+		self.check_pins();
+		self.collection.truncate(len);
+		#[cfg(feature = "check-pins")]
+		self.record_pins();
 	}
 
 	#[must_use]
 	pub fn as_slice(&self) -> &ItemsPin<role::Items, [T]> {
+		// SAFETY: `ItemsPin` is `#[repr(transparent)]`, and the slice covers the same pinned items.
 		unsafe { &*(self.collection.as_slice() as *const _ as *const _) }
 	}
 
 	#[must_use]
 	pub fn as_mut_slice(&mut self) -> &mut ItemsPin<role::Items, [T]> {
+		// SAFETY: `ItemsPin` is `#[repr(transparent)]`, and the slice covers the same pinned items.
+		// The vector can't be reallocated while the slice borrows it.
 		unsafe { &mut *(self.collection.as_mut_slice() as *mut _ as *mut _) }
 	}
 
 	#[must_use]
 	pub fn leak(self) -> &'static mut ItemsPin<role::Items, [T]> {
-		unsafe { &mut *(self.collection.leak() as *mut _ as *mut _) }
+		// SAFETY: `ItemsPin` is `#[repr(transparent)]`. Leaking keeps the buffer allocated forever,
+		// so the items stay in place and are only dropped in place, if at all.
+		unsafe { &mut *(self.into_collection().leak() as *mut _ as *mut _) }
+	}
+}
+
+impl<F: Future> ItemsPin<role::Items, Vec<F>> {
+	/// Resolves to the index and output of the first future to complete, then drops all futures in place in index order.
+	pub fn race_pinned(&mut self) -> Race<'_, Vec<F>> {
+		Race::new(self, |futures| futures.truncate_pinned(0))
+	}
+}
+
+impl<F: Future<Output = Result<T, E>>, T, E> ItemsPin<role::Items, Vec<F>> {
+	/// Resolves to all outputs in index order, or to the index and error of the first future to fail,
+	/// then drops all futures in place in index order.
+	pub fn try_join_all_pinned(&mut self) -> TryJoinAll<'_, Vec<F>, T> {
+		TryJoinAll::new(self, |futures| futures.truncate_pinned(0))
+	}
+}
+
+impl<T: PollClose> ItemsPin<role::Items, Vec<T>> {
+	/// Resolves once all items have reported that they are closed and have been dropped in place.
+	///
+	/// Each closed item is dropped as soon as all items after it are closed too, so the vector shrinks from the back.
+	/// Items are polled in index order and not again once closed.
+	///
+	/// Dropping the returned future early keeps the closed items that weren't dropped yet,
+	/// so a later `.close_all_pinned` polls them again.
+	pub fn close_all_pinned(&mut self) -> CloseAllPinned<'_, T> {
+		CloseAllPinned {
+			vec: self,
+			closed: Vec::new(),
+		}
+	}
+}
+
+/// Closes the items of an [`ItemsPin<role::Items, Vec<T>>`] concurrently. See `.close_all_pinned`.
+#[must_use = "futures do nothing unless polled"]
+pub struct CloseAllPinned<'a, T> {
+	vec: &'a mut ItemsPin<role::Items, Vec<T>>,
+	/// Parallel to the items that haven't been dropped yet.
+	closed: Vec<bool>,
+}
+
+impl<T: PollClose> Future for CloseAllPinned<'_, T> {
+	type Output = ();
+
+	fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+		let this = self.get_mut();
+		this.closed.resize(this.vec.collection.len(), false);
+
+		for (item, closed) in this.vec.items_pinned_mut().zip(&mut this.closed) {
+			if !*closed {
+				*closed = item.poll_close(cx).is_ready();
+			}
+		}
+
+		let len = this
+			.closed
+			.iter()
+			.rposition(|closed| !closed)
+			.map_or(0, |i| i + 1);
+		this.vec.truncate_pinned(len);
+		this.closed.truncate(len);
+
+		if len == 0 {
+			Poll::Ready(())
+		} else {
+			Poll::Pending
+		}
+	}
+}
+
+impl<T> ItemsPin<role::Items, Vec<Vec<T>>> {
+	pub fn items_pin_nested_mut(&mut self) -> NestedMut<'_, T> {
+		NestedMut {
+			iter: self.collection.iter_mut(),
+		}
+	}
+
+	#[allow(clippy::type_complexity)]
+	pub fn items_pinned_flat_mut(
+		&mut self,
+	) -> FlatMap<
+		slice::IterMut<'_, Vec<T>>,
+		PinIter<slice::IterMut<'_, T>>,
+		fn(&mut Vec<T>) -> PinIter<slice::IterMut<'_, T>>,
+	> {
+		self.collection
+			.iter_mut()
+			.flat_map(|inner| unsafe { PinIter::new_unchecked(inner.iter_mut()) })
+	}
+}
+
+/// Projects to the inner vectors of an [`ItemsPin<role::Items, Vec<Vec<T>>>`] as [`&mut ItemsPin<role::Items, Vec<T>>`](`ItemsPin`).
+pub struct NestedMut<'a, T> {
+	iter: slice::IterMut<'a, Vec<T>>,
+}
+
+impl<'a, T> Iterator for NestedMut<'a, T> {
+	type Item = &'a mut ItemsPin<role::Items, Vec<T>>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.iter
+			.next()
+			// SAFETY: The items of each inner vector stay in place until dropped:
+			// - The outer `ItemsPin`'s `ItemsMut` and `DerefMut` implementations, the only ones that hand out
+			//   `&mut Vec<T>`, require `Vec<T>: Unpin`. `Vec<T>` is `Unpin` only if `T` is
+			//   (there's no unconditional `impl Unpin`), in which case the items may be moved anyway.
+			// - Otherwise, the inner vectors are only exposed as `Pin<&mut Vec<T>>` or through this projection.
+			// - `&mut ItemsPin<role::Items, Vec<T>>` only allows operations that don't move items
+			//   (`.push_pinned` within capacity, `.truncate_pinned`, …). Swapping two of them moves
+			//   the vectors' buffer pointers, not the items.
+			// - The references are unique, as they come from `slice::IterMut`, and live no longer than `&mut self`.
+			.map(|inner| unsafe { ItemsPin::new_mut_unchecked(inner) })
+	}
+}
+
+#[cfg(feature = "rayon")]
+impl<T> ItemsPin<role::Items, Vec<T>> {
+	#[must_use]
+	pub fn par_items_pinned(&self) -> PinParIter<rayon::slice::Iter<'_, T>>
+	where
+		T: Sync,
+	{
+		self.as_slice().par_items_pinned()
+	}
+
+	pub fn par_items_pinned_mut(&mut self) -> PinParIter<rayon::slice::IterMut<'_, T>>
+	where
+		T: Send,
+	{
+		self.as_mut_slice().par_items_pinned_mut()
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<T> ItemsPin<role::Items, Vec<T>> {
+	/// Deserialises a [`Vec<T>`] and pins it immediately.
+	///
+	/// # Errors
+	///
+	/// Iff deserialising the [`Vec<T>`] fails.
+	pub fn deserialize_pinned<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>
+	where
+		T: Deserialize<'de>,
+	{
+		Vec::deserialize(deserializer).map(Self::pin)
+	}
+
+	/// Deserialises a [`Vec<T>`], reserves capacity for at least `additional` further items and then pins it.
+	///
+	/// The reserved capacity can be filled via `.push_pinned`.
+	///
+	/// # Errors
+	///
+	/// Iff deserialising the [`Vec<T>`] fails.
+	///
+	/// # Panics
+	///
+	/// Iff the new capacity exceeds [`isize::MAX`] bytes.
+	pub fn deserialize_pinned_with_reserve<'de, D: Deserializer<'de>>(
+		deserializer: D,
+		additional: usize,
+	) -> Result<Self, D::Error>
+	where
+		T: Deserialize<'de>,
+	{
+		Vec::deserialize(deserializer).map(|mut vec| {
+			vec.reserve_exact(additional);
+			Self::pin(vec)
+		})
 	}
 }
@@ -0,0 +1,115 @@
+#![cfg(feature = "allocator-api2")]
+
+use allocator_api2::{
+	alloc::{AllocError, Allocator, Layout},
+	boxed::Box,
+	vec::Vec,
+};
+use napje::{role, ItemsPin, ItemsPinned, ItemsPinnedMut};
+use std::{cell::Cell, marker::PhantomPinned, ptr::NonNull};
+
+/// Hands out memory from a fixed buffer and never frees it.
+struct Bump {
+	buffer: NonNull<u8>,
+	size: usize,
+	used: Cell<usize>,
+}
+
+impl Bump {
+	/// Pinning requires a `'static` allocator, so this leaks a new one with a 1 KiB buffer.
+	fn leak() -> &'static Self {
+		let buffer: &'static mut [u8] = std::boxed::Box::leak(std::boxed::Box::new([0; 1024]));
+		std::boxed::Box::leak(std::boxed::Box::new(Self {
+			buffer: NonNull::new(buffer.as_mut_ptr()).unwrap(),
+			size: buffer.len(),
+			used: Cell::new(0),
+		}))
+	}
+
+	fn contains<T>(&self, item: &T) -> bool {
+		let address = item as *const T as usize;
+		let start = self.buffer.as_ptr() as usize;
+		(start..start + self.size).contains(&address)
+	}
+}
+
+unsafe impl Allocator for &Bump {
+	fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+		let start = self.buffer.as_ptr() as usize + self.used.get();
+		let offset = (layout.align() - start % layout.align()) % layout.align();
+		let used = self.used.get() + offset + layout.size();
+		if used > self.size {
+			return Err(AllocError);
+		}
+		let pointer = unsafe { self.buffer.as_ptr().add(self.used.get() + offset) };
+		self.used.set(used);
+		Ok(NonNull::slice_from_raw_parts(
+			NonNull::new(pointer).unwrap(),
+			layout.size(),
+		))
+	}
+
+	unsafe fn deallocate(&self, _: NonNull<u8>, _: Layout) {}
+}
+
+struct Item<'a> {
+	id: usize,
+	dropped: &'a Cell<usize>,
+	_pinned: PhantomPinned,
+}
+
+impl Drop for Item<'_> {
+	fn drop(&mut self) {
+		self.dropped.set(self.dropped.get() + 1)
+	}
+}
+
+#[test]
+fn vec_in_bump() {
+	let bump = Bump::leak();
+	let dropped = Cell::new(0);
+	let item = |id| Item {
+		id,
+		dropped: &dropped,
+		_pinned: PhantomPinned,
+	};
+
+	let mut vec = Vec::with_capacity_in(2, bump);
+	vec.push(item(0));
+	let mut vec = ItemsPin::<role::Items, Vec<_, _>>::pin(vec);
+
+	assert!(vec.push_pinned(item(1)).is_ok());
+	assert_eq!(vec.push_pinned(item(2)).map_err(|item| item.id), Err(2));
+	assert_eq!(dropped.get(), 1);
+
+	assert!(vec.items_pinned().all(|item| bump.contains(&*item)));
+	assert_eq!(vec.items_pinned_mut().map(|item| item.id).sum::<usize>(), 1);
+	assert_eq!(vec.as_slice().len(), 2);
+
+	assert!(vec.pop_pinned());
+	assert_eq!(dropped.get(), 2);
+	vec.truncate_pinned(0);
+	assert_eq!(dropped.get(), 3);
+	assert!(!vec.pop_pinned());
+}
+
+#[test]
+fn box_in_bump() {
+	let bump = Bump::leak();
+	let dropped = Cell::new(0);
+
+	let mut vec = Vec::with_capacity_in(1, bump);
+	vec.push(Item {
+		id: 0,
+		dropped: &dropped,
+		_pinned: PhantomPinned,
+	});
+
+	let slice =
+		ItemsPin::<role::Items, Box<[_], _>>::from_pin_box(Box::into_pin(vec.into_boxed_slice()));
+	assert!(slice.items_pinned().all(|item| bump.contains(&*item)));
+	assert_eq!(slice.as_deref().items_pinned().count(), 1);
+
+	drop(slice);
+	assert_eq!(dropped.get(), 1);
+}
@@ -0,0 +1,87 @@
+#![cfg(feature = "std")]
+
+use napje::{
+	cell::{PinCell, PinMutex},
+	role, ItemsPin, ItemsPinned,
+};
+use std::{rc::Rc, sync::Arc, thread};
+
+#[path = "common_.rs"]
+mod common;
+use common::{item, Item};
+
+fn ids(pool: &ItemsPin<role::Items, Vec<Item>>) -> Vec<usize> {
+	pool.items_pinned().map(|item| item.id).collect()
+}
+
+#[test]
+fn pin_cell_through_rc() {
+	let pool = Rc::new(PinCell::new(ItemsPin::<role::Items, Vec<_>>::pin(
+		Vec::with_capacity(2),
+	)));
+	let shared = Rc::clone(&pool);
+
+	pool.borrow_pinned_mut().push_pinned(item(0)).ok().unwrap();
+	shared
+		.borrow_pinned_mut()
+		.push_pinned(item(1))
+		.ok()
+		.unwrap();
+	let first = &*pool.borrow_pinned().items_pinned().next().unwrap() as *const Item;
+
+	{
+		let borrowed = pool.borrow_pinned();
+		assert!(shared.try_borrow_pinned().is_ok());
+		assert!(shared.try_borrow_pinned_mut().is_err());
+		assert_eq!(ids(&borrowed), [0, 1]);
+	}
+
+	{
+		let mut borrowed = shared.borrow_pinned_mut();
+		assert!(pool.try_borrow_pinned().is_err());
+		assert!(borrowed.pop_pinned());
+	}
+
+	drop(shared);
+	let pool = Rc::try_unwrap(pool).ok().unwrap().into_inner();
+	assert_eq!(ids(&pool), [0]);
+	assert_eq!(&*pool.items_pinned().next().unwrap() as *const Item, first);
+}
+
+#[test]
+#[should_panic = "already borrowed"]
+fn pin_cell_borrow_conflict_panics() {
+	let pool = PinCell::new(ItemsPin::<role::Items, Vec<Item>>::pin(Vec::new()));
+	let _borrowed = pool.borrow_pinned();
+	let _ = pool.borrow_pinned_mut();
+}
+
+#[test]
+fn pin_mutex_across_threads() {
+	let pool = Arc::new(PinMutex::new(ItemsPin::<role::Items, Vec<_>>::pin(
+		Vec::with_capacity(4),
+	)));
+
+	let threads: Vec<_> = (0..4)
+		.map(|id| {
+			let pool = Arc::clone(&pool);
+			thread::spawn(move || {
+				pool.lock_pinned()
+					.unwrap()
+					.push_pinned(item(id))
+					.ok()
+					.unwrap()
+			})
+		})
+		.collect();
+	for thread in threads {
+		thread.join().unwrap();
+	}
+
+	let mut pool = Arc::try_unwrap(pool).ok().unwrap();
+	assert!(!pool.is_poisoned());
+	let mut ids = ids(pool.get_mut().unwrap());
+	ids.sort_unstable();
+	assert_eq!(ids, [0, 1, 2, 3]);
+	assert!(pool.try_lock_pinned().is_ok());
+}
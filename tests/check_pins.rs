@@ -0,0 +1,141 @@
+#![cfg(feature = "check-pins")]
+
+use napje::{collections::HoleyVec, role, ItemsPin, ItemsPinned};
+use std::{collections::LinkedList, iter, mem};
+
+#[path = "common_.rs"]
+mod common;
+use common::{item, Item};
+
+fn pinned_vec(len: usize, capacity: usize) -> ItemsPin<role::Items, Vec<Item>> {
+	let mut vec = Vec::with_capacity(capacity);
+	vec.extend((0..len).map(|_| item(0)));
+	ItemsPin::<role::Items, Vec<_>>::pin(vec)
+}
+
+/// Moves the items of `vec` into a new buffer, like a reallocation that can't grow in place.
+fn reallocate(vec: &mut Vec<Item>) {
+	let mut moved = Vec::with_capacity(vec.capacity() + 1);
+	moved.append(vec);
+	*vec = moved;
+}
+
+#[test]
+fn in_place_changes_pass() {
+	let mut vec = pinned_vec(3, 4);
+
+	unsafe { ItemsPin::inner_mut_unchecked(&mut vec) }.push(item(0));
+	assert_eq!(vec.items_pinned().count(), 4);
+
+	unsafe { ItemsPin::inner_mut_unchecked(&mut vec) }.truncate(1);
+	assert_eq!(vec.items_pinned().count(), 1);
+	assert!(vec.pop_pinned());
+}
+
+#[test]
+#[should_panic = "napje check-pins: item 0 of `alloc::vec::Vec<check_pins::common::Item>`"]
+fn reallocation_panics_on_access() {
+	let mut vec = pinned_vec(2, 2);
+
+	reallocate(unsafe { ItemsPin::inner_mut_unchecked(&mut vec) });
+	let _ = vec.items_pinned();
+}
+
+#[test]
+#[should_panic = "napje check-pins: item 0 of `alloc::vec::Vec<check_pins::common::Item>`"]
+fn reallocation_panics_on_drop_in_place() {
+	let mut vec = pinned_vec(1, 1);
+
+	reallocate(unsafe { ItemsPin::inner_mut_unchecked(&mut vec) });
+	vec.pop_pinned();
+}
+
+#[test]
+fn records_are_refreshed() {
+	let mut vec = pinned_vec(2, 2);
+
+	unsafe { ItemsPin::inner_mut_unchecked(&mut vec) }.reserve(0);
+	let _ = vec.items_pinned();
+	assert!(vec.push_pinned(item(0)).is_err());
+	let _ = vec.items_pinned();
+}
+
+#[test]
+fn reassignment_discards_the_record() {
+	let mut vec = pinned_vec(0, 2);
+	assert!(vec.push_pinned(item(0)).is_ok());
+	unsafe { ItemsPin::inner_mut_unchecked(&mut vec) }.push(item(0));
+
+	// Drops the old collection and stores a new one at the same address.
+	vec = ItemsPin::<role::Items, Vec<_>>::pin(vec![item(0)]);
+	let _ = vec.items_pinned();
+}
+
+#[test]
+#[should_panic = "napje check-pins: item 0 of `alloc::vec::Vec<check_pins::common::Item>`"]
+fn reallocation_panics_on_drop() {
+	let mut vec = pinned_vec(1, 1);
+
+	// Dropped in place at the end of the scope. (`drop(vec)` would move it first.)
+	reallocate(unsafe { ItemsPin::inner_mut_unchecked(&mut vec) });
+}
+
+#[test]
+#[should_panic = "napje check-pins: item 0 of `napje::collections::holey_vec::HoleyVec<check_pins::common::Item>`"]
+fn moving_out_panics_on_indexed_access() {
+	let mut vec = ItemsPin::<role::Items, HoleyVec<_>>::pin(HoleyVec::new());
+	vec.insert_pinned(item(0));
+
+	let holey_vec = unsafe { ItemsPin::inner_mut_unchecked(&mut vec) };
+	let mut old = mem::take(holey_vec);
+	holey_vec.insert(old.remove(0).unwrap());
+	let _ = vec.get_pinned(0);
+}
+
+#[test]
+#[should_panic = "napje check-pins: item 0 of `alloc::collections::linked_list::LinkedList<check_pins::common::Item>`"]
+fn moving_a_borrowed_collection_panics_on_access() {
+	let mut list: LinkedList<_> = (0..2).map(item).collect();
+	let _ = unsafe { ItemsPin::<role::Items, _>::new_mut_unchecked(&mut list) }.items_pinned();
+
+	// Moves the front item out (instead of dropping it in place), without `ItemsPin::inner_mut_unchecked`.
+	list.push_back(item(2));
+	let _front = list.pop_front();
+	let _ = unsafe { ItemsPin::<role::Items, _>::new_mut_unchecked(&mut list) }.items_pinned();
+}
+
+#[test]
+fn dropping_in_place_updates_the_record() {
+	let mut list = ItemsPin::<role::Items, LinkedList<_>>::pin((0..2).map(item).collect());
+	let _ = list.items_pinned();
+
+	assert!(list.pop_front_pinned());
+	// Likely takes over the dropped item's memory, so that the next one is stored elsewhere.
+	let _other: LinkedList<_> = iter::once(item(2)).collect();
+	list.push_back_pinned(item(3));
+	assert_eq!(list.items_pinned().count(), 2);
+}
+
+#[test]
+#[should_panic = "napje check-pins: item 0 of `napje::collections::holey_vec::HoleyVec<check_pins::common::Item>`"]
+fn moving_out_panics_on_removal() {
+	let mut vec = ItemsPin::<role::Items, HoleyVec<_>>::pin(HoleyVec::new());
+	vec.insert_pinned(item(0));
+
+	let holey_vec = unsafe { ItemsPin::inner_mut_unchecked(&mut vec) };
+	let mut old = mem::take(holey_vec);
+	holey_vec.insert(old.remove(0).unwrap());
+	vec.remove_pinned(0);
+}
+
+#[test]
+#[should_panic = "napje check-pins: item 1 of `alloc::vec::Vec<check_pins::common::Item>` was pinned at"]
+fn moving_out_panics_on_drop() {
+	let mut vec = pinned_vec(2, 2);
+	let _ = vec.items_pinned();
+
+	// Moves an item out (instead of dropping it in place), bypassing `ItemsPin` entirely.
+	let inner =
+		unsafe { &mut *(&mut vec as *mut ItemsPin<role::Items, Vec<Item>>).cast::<Vec<Item>>() };
+	let _moved = inner.pop();
+}
@@ -0,0 +1,177 @@
+#![cfg(feature = "alloc")]
+
+use napje::{collections::HoleyVec, future::PollClose, role, ItemsPin};
+use std::{
+	cell::RefCell,
+	future::Future,
+	marker::PhantomPinned,
+	pin::Pin,
+	sync::Arc,
+	task::{Context, Poll, Wake, Waker},
+};
+
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+	fn wake(self: Arc<Self>) {}
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Event {
+	Closed(usize),
+	Dropped(usize),
+}
+
+/// Closes on its `delay + 1`st poll.
+struct Connection<'a> {
+	id: usize,
+	delay: usize,
+	log: &'a RefCell<Vec<Event>>,
+	_pinned: PhantomPinned,
+}
+
+impl PollClose for Connection<'_> {
+	fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+		let this = unsafe { self.get_unchecked_mut() };
+		if this.delay == 0 {
+			this.log.borrow_mut().push(Event::Closed(this.id));
+			Poll::Ready(())
+		} else {
+			this.delay -= 1;
+			Poll::Pending
+		}
+	}
+}
+
+impl Drop for Connection<'_> {
+	fn drop(&mut self) {
+		self.log.borrow_mut().push(Event::Dropped(self.id))
+	}
+}
+
+fn poll_once(future: &mut (impl Future<Output = ()> + Unpin)) -> bool {
+	let waker = Waker::from(Arc::new(NoopWaker));
+	Pin::new(future)
+		.poll(&mut Context::from_waker(&waker))
+		.is_ready()
+}
+
+#[test]
+fn vec() {
+	use Event::{Closed, Dropped};
+
+	let log = RefCell::new(Vec::new());
+	let connection = |id, delay| Connection {
+		id,
+		delay,
+		log: &log,
+		_pinned: PhantomPinned,
+	};
+
+	let mut connections = ItemsPin::<role::Items, Vec<_>>::pin(vec![
+		connection(0, 1),
+		connection(1, 0),
+		connection(2, 0),
+	]);
+	let mut close_all = connections.close_all_pinned();
+
+	assert!(!poll_once(&mut close_all));
+	// Items 1 and 2 closed, so they can be dropped from the back.
+	assert_eq!(
+		*log.borrow(),
+		[Closed(1), Closed(2), Dropped(1), Dropped(2)]
+	);
+
+	log.borrow_mut().clear();
+	assert!(poll_once(&mut close_all));
+	assert_eq!(*log.borrow(), [Closed(0), Dropped(0)]);
+	assert!(connections.is_empty());
+}
+
+#[test]
+fn vec_keeps_closed_items_before_open_ones() {
+	use Event::{Closed, Dropped};
+
+	let log = RefCell::new(Vec::new());
+	let connection = |id, delay| Connection {
+		id,
+		delay,
+		log: &log,
+		_pinned: PhantomPinned,
+	};
+
+	let mut connections =
+		ItemsPin::<role::Items, Vec<_>>::pin(vec![connection(0, 0), connection(1, 1)]);
+	let mut close_all = connections.close_all_pinned();
+
+	assert!(!poll_once(&mut close_all));
+	assert_eq!(*log.borrow(), [Closed(0)]);
+
+	// Item 0 isn't polled again.
+	assert!(poll_once(&mut close_all));
+	assert_eq!(
+		*log.borrow(),
+		[Closed(0), Closed(1), Dropped(0), Dropped(1)]
+	);
+}
+
+#[test]
+fn vec_repolls_closed_items_after_restart() {
+	use Event::{Closed, Dropped};
+
+	let log = RefCell::new(Vec::new());
+	let connection = |id, delay| Connection {
+		id,
+		delay,
+		log: &log,
+		_pinned: PhantomPinned,
+	};
+
+	let mut connections =
+		ItemsPin::<role::Items, Vec<_>>::pin(vec![connection(0, 0), connection(1, 1)]);
+	assert!(!poll_once(&mut connections.close_all_pinned()));
+	assert_eq!(*log.borrow(), [Closed(0)]);
+
+	// Item 0 wasn't dropped, so the new future polls it again.
+	assert!(poll_once(&mut connections.close_all_pinned()));
+	assert_eq!(
+		*log.borrow(),
+		[Closed(0), Closed(0), Closed(1), Dropped(0), Dropped(1)]
+	);
+}
+
+#[test]
+fn holey_vec() {
+	use Event::{Closed, Dropped};
+
+	let log = RefCell::new(Vec::new());
+	let connection = |id, delay| Connection {
+		id,
+		delay,
+		log: &log,
+		_pinned: PhantomPinned,
+	};
+
+	let mut connections = ItemsPin::<role::Items, HoleyVec<_>>::pin(HoleyVec::new());
+	for delay in [1, 0, 2] {
+		connections.insert_pinned(connection(delay, delay));
+	}
+	let mut close_all = connections.close_all_pinned();
+
+	assert!(!poll_once(&mut close_all));
+	assert_eq!(*log.borrow(), [Closed(0), Dropped(0)]);
+	assert!(!poll_once(&mut close_all));
+	assert!(poll_once(&mut close_all));
+	assert_eq!(
+		*log.borrow(),
+		[
+			Closed(0),
+			Dropped(0),
+			Closed(1),
+			Dropped(1),
+			Closed(2),
+			Dropped(2)
+		]
+	);
+	assert!(connections.is_empty());
+}
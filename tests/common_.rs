@@ -0,0 +1,16 @@
+#![allow(dead_code)]
+
+use std::marker::PhantomPinned;
+
+/// A `!Unpin` item with an ID.
+pub struct Item {
+	pub id: usize,
+	_pinned: PhantomPinned,
+}
+
+pub fn item(id: usize) -> Item {
+	Item {
+		id,
+		_pinned: PhantomPinned,
+	}
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "alloc")]
+
+use napje::{collections::ConcurrentPinnedVec, role, ItemsPinned};
+use std::{
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+	thread,
+};
+
+struct Counted(Arc<AtomicUsize>);
+
+impl Drop for Counted {
+	fn drop(&mut self) {
+		self.0.fetch_add(1, Ordering::Relaxed);
+	}
+}
+
+#[test]
+fn push_from_many_threads() {
+	let drops = Arc::new(AtomicUsize::new(0));
+	let vec = Arc::new(ConcurrentPinnedVec::new());
+
+	let first = vec.push_pinned(Counted(drops.clone()));
+	let first_address = &*vec.get_pinned(first).unwrap() as *const Counted;
+
+	let threads = (0..8)
+		.map(|_| {
+			let vec = vec.clone();
+			let drops = drops.clone();
+			thread::spawn(move || {
+				for _ in 0..1000 {
+					vec.push_pinned(Counted(drops.clone()));
+				}
+			})
+		})
+		.collect::<Vec<_>>();
+	for thread in threads {
+		thread.join().unwrap();
+	}
+
+	assert_eq!(vec.len(), 8001);
+	assert_eq!(
+		ItemsPinned::<role::Items>::items_pinned(&*vec).count(),
+		8001
+	);
+	assert_eq!(
+		&*vec.get_pinned(first).unwrap() as *const Counted,
+		first_address
+	);
+
+	drop(vec);
+	assert_eq!(drops.load(Ordering::Relaxed), 8001);
+}
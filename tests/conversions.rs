@@ -0,0 +1,54 @@
+#![cfg(feature = "alloc")]
+
+use napje::{role, ItemsPin, ItemsPinned, ItemsPinnedMut};
+use std::pin::Pin;
+
+#[path = "common_.rs"]
+mod common;
+use common::{item, Item};
+
+fn ids(items: &ItemsPin<role::Items, [Item]>) -> Vec<usize> {
+	items.items_pinned().map(|item| item.id).collect()
+}
+
+#[test]
+fn from_pinned_slice() {
+	let mut array = [item(1), item(2)];
+	// Safety: `array` is shadowed and never moved again.
+	let array = unsafe { Pin::new_unchecked(&mut array[..]) };
+
+	let slice = ItemsPin::<role::Items, [Item]>::from_pin_mut(array);
+	assert_eq!(slice.items_pinned_mut().count(), 2);
+	assert_eq!(ids(slice), [1, 2]);
+}
+
+#[test]
+fn from_pinned_vec() {
+	// Safety: No code here accesses the vector without going through `ItemsPin`.
+	let mut vec = Box::pin(Vec::with_capacity(2));
+	let pinned = unsafe { ItemsPin::<role::Items, Vec<Item>>::from_pin_mut(vec.as_mut()) };
+	assert!(pinned.push_pinned(item(1)).is_ok());
+	assert_eq!(
+		ids(unsafe { ItemsPin::<role::Items, Vec<Item>>::from_pin_ref(vec.as_ref()) }.as_slice()),
+		[1]
+	);
+
+	let mut boxed = unsafe { ItemsPin::<role::Items, Box<Vec<Item>>>::from_pin_box(vec) };
+	assert!(boxed.as_deref_mut().push_pinned(item(2)).is_ok());
+	assert!(boxed.as_deref_mut().push_pinned(item(3)).is_err());
+	assert_eq!(ids(boxed.as_deref().as_slice()), [1, 2]);
+	assert_eq!(
+		boxed
+			.items_pinned()
+			.map(|item: Pin<&Item>| item.id)
+			.collect::<Vec<_>>(),
+		[1, 2]
+	);
+}
+
+#[test]
+fn from_pinned_boxed_slice() {
+	let boxed = Box::into_pin(vec![item(1), item(2)].into_boxed_slice());
+	let boxed = ItemsPin::<role::Items, Box<[Item]>>::from_pin_box(boxed);
+	assert_eq!(ids(boxed.as_deref()), [1, 2]);
+}
@@ -0,0 +1,19 @@
+#![cfg(feature = "alloc")]
+
+use napje::{role, ItemsPin};
+
+#[test]
+fn vec() {
+	let mut vec = Vec::with_capacity(4);
+	vec.extend([1, 2].iter().copied());
+	let pool = ItemsPin::<role::Items, Vec<u8>>::pin(vec);
+
+	assert_eq!(
+		format!("{:?}", pool),
+		"ItemsPin<Items> { len: 2, capacity: 4, items: [1, 2] }"
+	);
+	assert_eq!(
+		format!("{:?}", pool.as_slice()),
+		"ItemsPin<Items> { items: [1, 2] }"
+	);
+}
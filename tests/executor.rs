@@ -0,0 +1,152 @@
+#![cfg(feature = "executor")]
+
+use napje::executor::LocalPool;
+use std::{
+	cell::RefCell,
+	future::Future,
+	pin::Pin,
+	sync::{Arc, Mutex},
+	task::{Context, Poll, Waker},
+	thread,
+	time::Duration,
+};
+
+struct Guard<'a> {
+	id: usize,
+	log: &'a RefCell<Vec<usize>>,
+}
+
+impl Drop for Guard<'_> {
+	fn drop(&mut self) {
+		self.log.borrow_mut().push(self.id)
+	}
+}
+
+/// Wakes itself and returns [`Poll::Pending`] once.
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+	type Output = ();
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+		if self.0 {
+			Poll::Ready(())
+		} else {
+			self.0 = true;
+			cx.waker().wake_by_ref();
+			Poll::Pending
+		}
+	}
+}
+
+async fn count(id: usize, yields: usize, log: &RefCell<Vec<usize>>) -> *const Guard<'_> {
+	let guard = Guard { id, log };
+	let address = &guard as *const Guard;
+	for _ in 0..yields {
+		YieldNow(false).await;
+		// Holding a reference across `.await` makes the future `!Unpin`.
+		assert_eq!(&guard as *const Guard, address);
+	}
+	address
+}
+
+#[test]
+fn run_until_stalled_drops_completed_in_place() {
+	let dropped = RefCell::new(Vec::new());
+	let mut pool = LocalPool::new();
+
+	let handles: Vec<_> = (0..5)
+		.map(|id| pool.spawn(count(id, id, &dropped)))
+		.collect();
+	assert_eq!(pool.pending(), 5);
+	assert!(dropped.borrow().is_empty());
+
+	pool.run_until_stalled();
+	assert_eq!(pool.pending(), 0);
+	assert_eq!(*dropped.borrow(), [0, 1, 2, 3, 4]);
+
+	for &handle in &handles {
+		assert!(pool.is_finished(handle));
+		assert!(!pool.take_output(handle).unwrap().is_null());
+		assert!(!pool.is_finished(handle));
+		assert!(pool.take_output(handle).is_none());
+	}
+}
+
+#[test]
+fn handles_survive_slot_reuse() {
+	let dropped = RefCell::new(Vec::new());
+	let mut pool = LocalPool::new();
+
+	let first = pool.spawn(count(0, 0, &dropped));
+	pool.run_until_stalled();
+	pool.take_output(first).unwrap();
+
+	let second = pool.spawn(count(1, 0, &dropped));
+	assert_ne!(first, second);
+	assert!(pool.take_output(first).is_none());
+	pool.run_until_stalled();
+	assert!(pool.take_output(second).is_some());
+}
+
+#[test]
+fn pending_tasks_are_dropped_with_the_pool() {
+	let dropped = RefCell::new(Vec::new());
+	let mut pool = LocalPool::new();
+	pool.spawn(count(0, 0, &dropped));
+	pool.spawn(count(1, 0, &dropped));
+
+	drop(pool);
+	// Never polled, so the guards were never created.
+	assert!(dropped.borrow().is_empty());
+}
+
+/// Completes once its shared flag is set, storing the latest waker otherwise.
+struct Signal(Arc<Mutex<(bool, Option<Waker>)>>);
+
+impl Future for Signal {
+	type Output = ();
+
+	fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+		let mut state = self.0.lock().unwrap();
+		if state.0 {
+			Poll::Ready(())
+		} else {
+			state.1 = Some(cx.waker().clone());
+			Poll::Pending
+		}
+	}
+}
+
+#[test]
+fn run_parks_until_woken_from_another_thread() {
+	let states: Vec<_> = (0..3)
+		.map(|_| Arc::new(Mutex::new((false, None::<Waker>))))
+		.collect();
+	let mut pool = LocalPool::new();
+	let handles: Vec<_> = states
+		.iter()
+		.map(|state| pool.spawn(Signal(Arc::clone(state))))
+		.collect();
+
+	pool.run_until_stalled();
+	assert_eq!(pool.pending(), 3);
+
+	let waker_thread = thread::spawn({
+		let states = states.clone();
+		move || {
+			for state in states.iter().rev() {
+				thread::sleep(Duration::from_millis(10));
+				let mut state = state.lock().unwrap();
+				state.0 = true;
+				state.1.take().unwrap().wake();
+			}
+		}
+	});
+
+	pool.run();
+	waker_thread.join().unwrap();
+	for handle in handles {
+		assert_eq!(pool.take_output(handle), Some(()));
+	}
+}
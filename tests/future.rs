@@ -0,0 +1,186 @@
+#![cfg(feature = "alloc")]
+
+use napje::{role, ItemsPin};
+use std::{
+	cell::RefCell,
+	future::Future,
+	marker::PhantomPinned,
+	pin::Pin,
+	sync::Arc,
+	task::{Context, Poll, Wake, Waker},
+};
+
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+	fn wake(self: Arc<Self>) {}
+}
+
+/// Polls `future` until it's ready, returning the number of polls.
+fn block_on<F: Future + Unpin>(mut future: F) -> (usize, F::Output) {
+	let waker = Waker::from(Arc::new(NoopWaker));
+	let mut cx = Context::from_waker(&waker);
+	for polls in 1.. {
+		if let Poll::Ready(output) = Pin::new(&mut future).poll(&mut cx) {
+			return (polls, output);
+		}
+	}
+	unreachable!()
+}
+
+/// Completes with `output` on its `delay + 1`st poll.
+struct Delay<'a> {
+	id: usize,
+	delay: usize,
+	output: Option<Result<usize, usize>>,
+	polled: &'a RefCell<Vec<usize>>,
+	dropped: &'a RefCell<Vec<usize>>,
+	_pinned: PhantomPinned,
+}
+
+impl Future for Delay<'_> {
+	type Output = Result<usize, usize>;
+
+	fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+		let this = unsafe { self.get_unchecked_mut() };
+		this.polled.borrow_mut().push(this.id);
+		if this.delay == 0 {
+			Poll::Ready(this.output.take().expect("polled after completion"))
+		} else {
+			this.delay -= 1;
+			Poll::Pending
+		}
+	}
+}
+
+impl Drop for Delay<'_> {
+	fn drop(&mut self) {
+		self.dropped.borrow_mut().push(self.id)
+	}
+}
+
+#[test]
+fn race_vec() {
+	let polled = RefCell::new(Vec::new());
+	let dropped = RefCell::new(Vec::new());
+	let delay = |id, delay| Delay {
+		id,
+		delay,
+		output: Some(Ok(id * 10)),
+		polled: &polled,
+		dropped: &dropped,
+		_pinned: PhantomPinned,
+	};
+
+	let mut futures =
+		ItemsPin::<role::Items, Vec<_>>::pin(vec![delay(0, 3), delay(1, 1), delay(2, 1)]);
+	assert_eq!(block_on(futures.race_pinned()), (2, (1, Ok(10))));
+	assert_eq!(*polled.borrow(), [0, 1, 2, 0, 1]);
+	assert_eq!(*dropped.borrow(), [0, 1, 2]);
+	assert!(futures.is_empty());
+}
+
+#[test]
+fn race_slice() {
+	let polled = RefCell::new(Vec::new());
+	let dropped = RefCell::new(Vec::new());
+	let mut futures = ItemsPin::<role::Items, Vec<_>>::pin(
+		(0..4)
+			.map(|id| Delay {
+				id,
+				delay: 3 - id,
+				output: Some(Err(id)),
+				polled: &polled,
+				dropped: &dropped,
+				_pinned: PhantomPinned,
+			})
+			.collect(),
+	);
+
+	assert_eq!(
+		block_on(futures.as_mut_slice().race_pinned()),
+		(1, (3, Err(3)))
+	);
+	// The slice's futures stay in place until the vector is dropped.
+	assert!(dropped.borrow().is_empty());
+	drop(futures);
+	assert_eq!(*dropped.borrow(), [0, 1, 2, 3]);
+}
+
+#[test]
+fn try_join_all_ok() {
+	let polled = RefCell::new(Vec::new());
+	let dropped = RefCell::new(Vec::new());
+	let delay = |id, delay| Delay {
+		id,
+		delay,
+		output: Some(Ok(id * 10)),
+		polled: &polled,
+		dropped: &dropped,
+		_pinned: PhantomPinned,
+	};
+
+	let mut futures =
+		ItemsPin::<role::Items, Vec<_>>::pin(vec![delay(0, 2), delay(1, 0), delay(2, 1)]);
+	assert_eq!(
+		block_on(futures.try_join_all_pinned()),
+		(3, Ok(vec![0, 10, 20]))
+	);
+	// Completed futures aren't polled again.
+	assert_eq!(*polled.borrow(), [0, 1, 2, 0, 2, 0]);
+	assert_eq!(*dropped.borrow(), [0, 1, 2]);
+	assert!(futures.is_empty());
+}
+
+#[test]
+fn try_join_all_err() {
+	let polled = RefCell::new(Vec::new());
+	let dropped = RefCell::new(Vec::new());
+	let delay = |id, delay, output| Delay {
+		id,
+		delay,
+		output: Some(output),
+		polled: &polled,
+		dropped: &dropped,
+		_pinned: PhantomPinned,
+	};
+
+	let mut futures = ItemsPin::<role::Items, Vec<_>>::pin(vec![
+		delay(0, 0, Ok(0)),
+		delay(1, 1, Err(1)),
+		delay(2, 1, Err(2)),
+		delay(3, 5, Ok(3)),
+	]);
+	assert_eq!(block_on(futures.try_join_all_pinned()), (2, Err((1, 1))));
+	// Stops at the first error, without polling the rest again.
+	assert_eq!(*polled.borrow(), [0, 1, 2, 3, 1]);
+	assert_eq!(*dropped.borrow(), [0, 1, 2, 3]);
+}
+
+#[test]
+fn try_join_all_empty() {
+	let mut futures = ItemsPin::<role::Items, Vec<Delay>>::pin(Vec::new());
+	assert_eq!(block_on(futures.try_join_all_pinned()), (1, Ok(vec![])));
+}
+
+#[test]
+fn try_join_all_slice() {
+	let polled = RefCell::new(Vec::new());
+	let dropped = RefCell::new(Vec::new());
+	let delay = |id, delay| Delay {
+		id,
+		delay,
+		output: Some(Ok(id * 10)),
+		polled: &polled,
+		dropped: &dropped,
+		_pinned: PhantomPinned,
+	};
+
+	let mut futures = ItemsPin::<role::Items, Vec<_>>::pin(vec![delay(0, 1), delay(1, 0)]);
+	assert_eq!(
+		block_on(futures.as_mut_slice().try_join_all_pinned()),
+		(2, Ok(vec![0, 10]))
+	);
+	assert!(dropped.borrow().is_empty());
+	assert_eq!(futures.len(), 2);
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "alloc")]
+
+use napje::{collections::HoleyVec, role, ItemsPin, ItemsPinned};
+use std::{cell::RefCell, marker::PhantomPinned, pin::Pin};
+
+struct Task<'a> {
+	id: usize,
+	log: &'a RefCell<Vec<usize>>,
+	_pinned: PhantomPinned,
+}
+
+impl Drop for Task<'_> {
+	fn drop(&mut self) {
+		self.log.borrow_mut().push(self.id)
+	}
+}
+
+#[test]
+fn retain_and_reuse_holes() {
+	let dropped = RefCell::new(Vec::new());
+	let task = |id| Task {
+		id,
+		log: &dropped,
+		_pinned: PhantomPinned,
+	};
+
+	let mut tasks = ItemsPin::<role::Items, HoleyVec<_>>::pin(HoleyVec::new());
+	for id in 0..100 {
+		assert_eq!(tasks.insert_pinned(task(id)), id);
+	}
+	let survivor = &*tasks.get_pinned(52).unwrap() as *const Task;
+
+	tasks.retain_pinned(|task: Pin<&mut Task>| task.id % 3 == 1);
+	assert_eq!(dropped.borrow().len(), 67);
+	assert_eq!(dropped.borrow()[..4], [0, 2, 3, 5]);
+	assert_eq!(tasks.len(), 33);
+	assert_eq!(tasks.end(), 100);
+	assert_eq!(&*tasks.get_pinned(52).unwrap() as *const _, survivor);
+	assert!(tasks.get_pinned(51).is_none());
+
+	let ids = tasks.items_pinned().map(|task| task.id).collect::<Vec<_>>();
+	assert_eq!(ids, (1..100).step_by(3).collect::<Vec<_>>());
+
+	dropped.borrow_mut().clear();
+	assert!(tasks.remove_pinned(52));
+	assert!(!tasks.remove_pinned(52));
+	assert_eq!(*dropped.borrow(), [52]);
+
+	// The most recently vacated hole is filled first.
+	assert_eq!(tasks.insert_pinned(task(1000)), 52);
+	assert_eq!(tasks.get_pinned_mut(52).unwrap().id, 1000);
+
+	dropped.borrow_mut().clear();
+	drop(tasks);
+	assert_eq!(dropped.borrow().len(), 33);
+}
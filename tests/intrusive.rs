@@ -0,0 +1,81 @@
+#![cfg(feature = "alloc")]
+
+use napje::{
+	intrusive::{IntrusiveList, Link, Linked},
+	role, ItemsPin, ItemsPinned,
+};
+use std::pin::Pin;
+
+struct Entry {
+	value: u8,
+	link: Link,
+}
+
+unsafe impl Linked for Entry {
+	fn link(self: Pin<&Self>) -> Pin<&Link> {
+		unsafe { self.map_unchecked(|entry| &entry.link) }
+	}
+}
+
+fn values(
+	list: Pin<&IntrusiveList<Entry>>,
+	entries: &ItemsPin<role::Items, Vec<Entry>>,
+) -> Vec<u8> {
+	list.iter_in(entries.as_slice())
+		.map(|entry| entry.value)
+		.collect()
+}
+
+#[test]
+fn unlink_on_drop() {
+	let mut entries = ItemsPin::<role::Items, Vec<_>>::pin(Vec::with_capacity(3));
+	for value in 0..3 {
+		entries
+			.push_pinned(Entry {
+				value,
+				link: Link::new(),
+			})
+			.unwrap_or_else(|_| unreachable!());
+	}
+
+	let list = Box::pin(IntrusiveList::new());
+	for entry in entries.items_pinned() {
+		list.as_ref().push_front(entry);
+	}
+	assert_eq!(values(list.as_ref(), &entries), [2, 1, 0]);
+
+	// Touch.
+	let first = entries.as_slice().items_pinned().next().unwrap();
+	list.as_ref().push_front(first);
+	assert_eq!(values(list.as_ref(), &entries), [0, 2, 1]);
+
+	assert!(entries.pop_pinned());
+	assert_eq!(values(list.as_ref(), &entries), [0, 1]);
+	assert_eq!(list.as_ref().len(), 2);
+
+	assert_eq!(
+		list.as_ref()
+			.back_in(entries.as_slice())
+			.map(|entry| entry.value),
+		Some(1)
+	);
+
+	entries.truncate_pinned(0);
+	assert!(list.as_ref().is_empty());
+}
+
+#[test]
+fn drop_list_first() {
+	let entries = ItemsPin::<role::Items, Vec<_>>::pin(vec![Entry {
+		value: 0,
+		link: Link::new(),
+	}]);
+
+	let list = Box::pin(IntrusiveList::new());
+	list.as_ref()
+		.push_back(entries.as_slice().items_pinned().next().unwrap());
+	drop(list);
+
+	let entry = entries.as_slice().items_pinned().next().unwrap();
+	assert!(!entry.link().is_linked());
+}
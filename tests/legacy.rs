@@ -0,0 +1,25 @@
+#![cfg(feature = "alloc")]
+#![allow(deprecated)]
+
+use napje::{
+	legacy::{Items, ItemsPinnedMut},
+	role, ItemsPin,
+};
+
+fn sum<'a, C: ?Sized + Items<'a, role::Items, Item = u8>>(collection: &'a C) -> u8 {
+	collection.items().sum()
+}
+
+fn increment_all<'a, C: ItemsPinnedMut<'a, role::Items, Item = u8>>(collection: &'a mut C) {
+	for mut item in collection.items_pinned_mut() {
+		*item += 1;
+	}
+}
+
+#[test]
+fn old_bounds() {
+	let mut pool = ItemsPin::<role::Items, Vec<u8>>::pin(vec![1, 2, 3]);
+	increment_all(&mut pool);
+	assert_eq!(sum(&pool), 9);
+	assert_eq!(sum(pool.as_slice()), 9);
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "alloc")]
+
+use napje::{role, ItemsPin, ItemsPinned};
+use std::{cell::RefCell, collections::LinkedList, marker::PhantomPinned, pin::Pin};
+
+struct Item<'a> {
+	id: u8,
+	log: &'a RefCell<Vec<u8>>,
+	_pinned: PhantomPinned,
+}
+
+impl Drop for Item<'_> {
+	fn drop(&mut self) {
+		self.log.borrow_mut().push(self.id)
+	}
+}
+
+fn addresses(list: &ItemsPin<role::Items, LinkedList<Item>>) -> Vec<(u8, *const ())> {
+	list.items_pinned()
+		.map(|item: Pin<&Item>| (item.id, &*item as *const Item as *const _))
+		.collect()
+}
+
+fn ids(list: &ItemsPin<role::Items, LinkedList<Item>>) -> Vec<u8> {
+	list.items_pinned().map(|item| item.id).collect()
+}
+
+#[test]
+fn push_pop_and_cursor() {
+	let dropped = RefCell::new(Vec::new());
+	let item = |id| Item {
+		id,
+		log: &dropped,
+		_pinned: PhantomPinned,
+	};
+
+	let mut list = ItemsPin::<role::Items, LinkedList<_>>::pin(LinkedList::new());
+	for id in 1..=3 {
+		list.push_back_pinned(item(id));
+	}
+	list.push_front_pinned(item(0));
+	let before = addresses(&list);
+
+	assert!(list.pop_front_pinned());
+	assert!(list.pop_back_pinned());
+	assert_eq!(*dropped.borrow(), [0, 3]);
+	assert_eq!(addresses(&list), before[1..3]);
+
+	let mut cursor = list.cursor_front_pinned_mut();
+	cursor.move_next();
+	assert_eq!(cursor.current().map(|item| item.id), Some(2));
+	cursor.insert_before(item(10));
+	cursor.insert_after(item(20));
+	assert_eq!(cursor.index(), Some(2));
+	assert!(cursor.remove_current_pinned());
+	assert_eq!(cursor.current().map(|item| item.id), Some(20));
+	cursor.move_next();
+	assert_eq!(cursor.index(), None);
+	cursor.insert_after(item(30));
+	assert_eq!(*dropped.borrow(), [0, 3, 2]);
+	assert_eq!(ids(&list), [30, 1, 10, 20]);
+	assert_eq!(addresses(&list)[1], before[1]);
+
+	dropped.borrow_mut().clear();
+	drop(list);
+	assert_eq!(*dropped.borrow(), [30, 1, 10, 20]);
+}
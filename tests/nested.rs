@@ -0,0 +1,41 @@
+#![cfg(feature = "alloc")]
+
+use napje::{role, ItemsPin};
+use std::pin::Pin;
+
+#[path = "common_.rs"]
+mod common;
+use common::{item, Item};
+
+#[test]
+fn nested_projection() {
+	let mut outer = ItemsPin::<role::Items, Vec<Vec<_>>>::pin(
+		(0..3).map(|_| Vec::with_capacity(2)).collect::<Vec<_>>(),
+	);
+
+	for (i, inner) in outer.items_pin_nested_mut().enumerate() {
+		assert!(inner.push_pinned(item(i * 10)).is_ok());
+	}
+	let first = outer
+		.items_pinned_flat_mut()
+		.next()
+		.map(|item| &*item as *const Item);
+
+	for (i, inner) in outer.items_pin_nested_mut().enumerate() {
+		assert!(inner.push_pinned(item(i * 10 + 1)).is_ok());
+		assert!(inner.push_pinned(item(i * 10 + 2)).is_err());
+	}
+
+	let ids = outer
+		.items_pinned_flat_mut()
+		.map(|item: Pin<&mut Item>| item.id)
+		.collect::<Vec<_>>();
+	assert_eq!(ids, [0, 1, 10, 11, 20, 21]);
+	assert_eq!(
+		outer
+			.items_pinned_flat_mut()
+			.next()
+			.map(|item| &*item as *const _),
+		first
+	);
+}
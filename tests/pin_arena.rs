@@ -0,0 +1,66 @@
+#![cfg(feature = "alloc")]
+
+use napje::{collections::PinArena, role, ItemsPinned};
+use std::{
+	cell::{Cell, RefCell},
+	marker::PhantomPinned,
+	pin::Pin,
+};
+
+struct Node<'a> {
+	id: usize,
+	next: Cell<Option<Pin<&'a Node<'a>>>>,
+	_pinned: PhantomPinned,
+}
+
+#[test]
+fn graph() {
+	let arena = PinArena::new();
+	let node = |id| Node {
+		id,
+		next: Cell::new(None),
+		_pinned: PhantomPinned,
+	};
+
+	let first = arena.alloc(node(0));
+	let mut previous = first;
+	for id in 1..100 {
+		let current = arena.alloc(node(id));
+		previous.next.set(Some(current));
+		previous = current;
+	}
+	previous.next.set(Some(first));
+
+	let mut current = first;
+	for _ in 0..100 {
+		current = current.next.get().unwrap();
+	}
+	assert_eq!(&*current as *const Node, &*first as *const _);
+
+	let ids = ItemsPinned::<role::Items>::items_pinned(&arena)
+		.map(|node| node.id)
+		.collect::<Vec<_>>();
+	assert_eq!(ids, (0..100).collect::<Vec<_>>());
+}
+
+struct Logged<'a>(usize, &'a RefCell<Vec<usize>>);
+
+impl Drop for Logged<'_> {
+	fn drop(&mut self) {
+		self.1.borrow_mut().push(self.0)
+	}
+}
+
+#[test]
+fn drop_in_allocation_order() {
+	let dropped = RefCell::new(Vec::new());
+	let mut arena = PinArena::new();
+	for id in 0..50 {
+		arena.alloc(Logged(id, &dropped));
+	}
+	assert_eq!(arena.len(), 50);
+	assert_eq!(arena.items_pinned_mut().count(), 50);
+
+	drop(arena);
+	assert_eq!(*dropped.borrow(), (0..50).collect::<Vec<_>>());
+}
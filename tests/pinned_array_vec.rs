@@ -0,0 +1,53 @@
+use napje::collections::PinnedArrayVec;
+use std::{cell::Cell, marker::PhantomPinned};
+
+struct Tracked<'a> {
+	drops: &'a Cell<usize>,
+	_pinned: PhantomPinned,
+}
+
+impl<'a> Tracked<'a> {
+	fn new(drops: &'a Cell<usize>) -> Self {
+		Self {
+			drops,
+			_pinned: PhantomPinned,
+		}
+	}
+}
+
+impl Drop for Tracked<'_> {
+	fn drop(&mut self) {
+		self.drops.set(self.drops.get() + 1)
+	}
+}
+
+#[test]
+fn two_phases() {
+	let drops = Cell::new(0);
+
+	let mut vec = PinnedArrayVec::<_, 4>::new();
+	assert!(vec.push(Tracked::new(&drops)).is_ok());
+	drop(vec.pop());
+	assert_eq!(drops.get(), 1);
+	assert!(vec.push(Tracked::new(&drops)).is_ok());
+
+	let mut vec = Box::pin(vec);
+	let first = &*vec.as_ref().items_pinned().next().unwrap() as *const Tracked;
+	for _ in 1..4 {
+		assert!(vec.as_mut().push_pinned(Tracked::new(&drops)).is_ok());
+	}
+	assert!(vec.as_mut().push_pinned(Tracked::new(&drops)).is_err());
+	assert_eq!(drops.get(), 2);
+	assert_eq!(
+		&*vec.as_ref().items_pinned().next().unwrap() as *const Tracked,
+		first
+	);
+
+	assert!(vec.as_mut().pop_pinned());
+	vec.as_mut().truncate_pinned(1);
+	assert_eq!(drops.get(), 5);
+	assert_eq!(vec.as_ref().as_pinned_slice().len(), 1);
+
+	drop(vec);
+	assert_eq!(drops.get(), 6);
+}
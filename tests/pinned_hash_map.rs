@@ -0,0 +1,78 @@
+#![cfg(feature = "std")]
+
+use napje::{collections::PinnedHashMap, role, Items, ItemsPin, ItemsPinnedMut};
+use std::{cell::RefCell, marker::PhantomPinned, pin::Pin};
+
+struct Connection<'a> {
+	id: u32,
+	log: &'a RefCell<Vec<u32>>,
+	_pinned: PhantomPinned,
+}
+
+impl Drop for Connection<'_> {
+	fn drop(&mut self) {
+		self.log.borrow_mut().push(self.id)
+	}
+}
+
+#[test]
+fn registry() {
+	let dropped = RefCell::new(Vec::new());
+	let connection = |id| Connection {
+		id,
+		log: &dropped,
+		_pinned: PhantomPinned,
+	};
+
+	let mut registry = ItemsPin::<role::Values, PinnedHashMap<_, _>>::pin(PinnedHashMap::new());
+	let first = &*registry.insert_pinned("first", connection(0)) as *const Connection;
+
+	for id in 1..200 {
+		let inserted: Pin<&mut Connection> =
+			registry.insert_pinned(Box::leak(id.to_string().into_boxed_str()), connection(id));
+		assert_eq!(inserted.id, id);
+	}
+	assert_eq!(registry.len(), 200);
+	assert_eq!(&*registry.get_pinned("first").unwrap() as *const _, first);
+	assert_eq!(
+		&*registry.get_pinned_mut("first").unwrap() as *const _,
+		first
+	);
+	assert!(dropped.borrow().is_empty());
+
+	registry.insert_pinned("first", connection(1000));
+	assert_eq!(*dropped.borrow(), [0]);
+	assert_eq!(registry.get_pinned("first").unwrap().id, 1000);
+
+	assert!(registry.remove("7"));
+	assert!(!registry.remove("7"));
+	assert_eq!(*dropped.borrow(), [0, 7]);
+	assert_eq!(registry.items_pinned_mut().count(), 199);
+	assert_eq!(Items::<role::Values>::items(&*registry).count(), 199);
+
+	dropped.borrow_mut().clear();
+	drop(registry);
+	assert_eq!(dropped.borrow().len(), 199);
+}
+
+#[test]
+fn roles() {
+	let mut map = PinnedHashMap::new();
+	assert_eq!(map.insert("a", 1), None);
+	assert_eq!(map.insert("b", 2), None);
+	assert_eq!(map.insert("a", 3), Some(1));
+	assert_eq!(map.remove_entry("b"), Some(("b", 2)));
+
+	let keys = Items::<role::Keys>::items(&map)
+		.copied()
+		.collect::<Vec<_>>();
+	let entries = Items::<role::Entries>::items(&map)
+		.copied()
+		.collect::<Vec<_>>();
+	assert_eq!(keys, ["a"]);
+	assert_eq!(entries, [("a", 3)]);
+
+	let mut values = ItemsPin::<role::Values, _>::new(map);
+	*values.get_mut("a").unwrap() += 1;
+	assert_eq!(values.get_pinned("a").as_deref(), Some(&4));
+}
@@ -0,0 +1,94 @@
+#![cfg(feature = "std")]
+
+use napje::{collections::PinnedHashSet, role, ItemsPin, ItemsPinned};
+use std::{
+	cell::RefCell,
+	hash::{Hash, Hasher},
+	marker::PhantomPinned,
+	pin::Pin,
+};
+
+struct Name<'a> {
+	name: &'static str,
+	_pinned: PhantomPinned,
+	log: &'a RefCell<Vec<&'static str>>,
+}
+
+impl PartialEq for Name<'_> {
+	fn eq(&self, other: &Self) -> bool {
+		self.name == other.name
+	}
+}
+impl Eq for Name<'_> {}
+
+impl Hash for Name<'_> {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.name.hash(state)
+	}
+}
+
+impl Drop for Name<'_> {
+	fn drop(&mut self) {
+		self.log.borrow_mut().push(self.name)
+	}
+}
+
+#[test]
+fn insert_after_pinning() {
+	let dropped = RefCell::new(Vec::new());
+	let name = |name| Name {
+		name,
+		_pinned: PhantomPinned,
+		log: &dropped,
+	};
+
+	let mut set = ItemsPin::<role::Keys, PinnedHashSet<_>>::pin(PinnedHashSet::new());
+	assert!(set.insert_pinned(name("first")));
+	let first: Pin<&Name> = set.get_pinned(&name("first")).unwrap();
+	let first = &*first as *const Name;
+	assert_eq!(*dropped.borrow(), ["first"]);
+
+	// Enough to allocate several more chunks.
+	let names = (0..200)
+		.map(|i| &*Box::leak(format!("name {}", i).into_boxed_str()))
+		.collect::<Vec<_>>();
+	for &n in &names {
+		assert!(set.insert_pinned(name(n)));
+	}
+	assert!(!set.insert_pinned(name("first")));
+	assert_eq!(set.len(), 201);
+	assert_eq!(
+		&*set.get_or_insert_pinned(name("first")) as *const Name,
+		first
+	);
+	assert_eq!(&*set.get_pinned(&name("first")).unwrap() as *const _, first);
+	assert_eq!(set.items_pinned().count(), 201);
+
+	dropped.borrow_mut().clear();
+	assert!(set.remove_pinned(&name("name 7")));
+	assert!(!set.remove_pinned(&name("name 7")));
+	assert_eq!(*dropped.borrow(), ["name 7", "name 7", "name 7"]);
+	assert_eq!(set.len(), 200);
+
+	dropped.borrow_mut().clear();
+	drop(set);
+	assert_eq!(dropped.borrow().len(), 200);
+}
+
+#[test]
+fn borrowed_lookup() {
+	let mut set = PinnedHashSet::new();
+	assert!(set.insert(String::from("a")));
+	assert!(set.insert(String::from("b")));
+	assert!(set.contains("a"));
+	assert_eq!(set.take("a").as_deref(), Some("a"));
+	assert!(!set.contains("a"));
+
+	let mut set = ItemsPin::<role::Keys, _>::new(set);
+	assert!(set.insert_pinned(String::from("c")));
+	assert_eq!(
+		set.get_pinned("b").as_deref().map(String::as_str),
+		Some("b")
+	);
+	assert_eq!(set.len(), 2);
+}
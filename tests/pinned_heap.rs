@@ -0,0 +1,61 @@
+#![cfg(feature = "alloc")]
+
+use napje::{collections::PinnedHeap, role, ItemsPin, ItemsPinned};
+use std::{cell::RefCell, cmp::Reverse, marker::PhantomPinned, pin::Pin};
+
+struct Timer<'a> {
+	name: &'static str,
+	log: &'a RefCell<Vec<&'static str>>,
+	_pinned: PhantomPinned,
+}
+
+impl Drop for Timer<'_> {
+	fn drop(&mut self) {
+		self.log.borrow_mut().push(self.name)
+	}
+}
+
+#[test]
+fn earliest_deadline_first() {
+	let dropped = RefCell::new(Vec::new());
+	let timer = |name| Timer {
+		name,
+		log: &dropped,
+		_pinned: PhantomPinned,
+	};
+
+	let mut timers = ItemsPin::<role::Items, PinnedHeap<_, _>>::pin(PinnedHeap::new());
+	timers.push_pinned(Reverse(30), timer("c"));
+	timers.push_pinned(Reverse(10), timer("a"));
+	let (_, a) = timers.peek_pinned_mut().unwrap();
+	let a = &*a as *const Timer;
+
+	for deadline in 100..200 {
+		timers.push_pinned(Reverse(deadline), timer("late"));
+	}
+	timers.push_pinned(Reverse(20), timer("b"));
+	assert_eq!(timers.items_pinned().count(), 103);
+
+	let (deadline, address) = timers
+		.pop_pinned(|timer: Pin<&mut Timer>| {
+			assert!(dropped.borrow().is_empty());
+			&*timer as *const Timer
+		})
+		.unwrap();
+	assert_eq!((deadline, address), (Reverse(10), a));
+
+	let mut fired = Vec::new();
+	while let Some((_, name)) = timers.pop_pinned(|timer| timer.name) {
+		fired.push(name);
+		if name == "c" {
+			break;
+		}
+	}
+	assert_eq!(fired, ["b", "c"]);
+	assert_eq!(*dropped.borrow(), ["a", "b", "c"]);
+	assert_eq!(timers.len(), 100);
+
+	dropped.borrow_mut().clear();
+	drop(timers);
+	assert_eq!(dropped.borrow().len(), 100);
+}
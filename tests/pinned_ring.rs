@@ -0,0 +1,55 @@
+#![cfg(feature = "alloc")]
+
+use napje::{collections::PinnedRing, role, ItemsPin, ItemsPinnedMut};
+use std::{cell::RefCell, pin::Pin};
+
+struct Operation<'a> {
+	id: u8,
+	log: &'a RefCell<Vec<u8>>,
+}
+
+impl Drop for Operation<'_> {
+	fn drop(&mut self) {
+		self.log.borrow_mut().push(self.id)
+	}
+}
+
+#[test]
+fn overwrite_oldest() {
+	let dropped = RefCell::new(Vec::new());
+	let operation = |id| Operation { id, log: &dropped };
+
+	let mut ring = ItemsPin::<role::Items, PinnedRing<_>>::pin(PinnedRing::with_capacity(3));
+	for id in 0..3 {
+		assert!(ring.push_pinned(operation(id)).is_ok());
+	}
+	assert!(ring.push_pinned(operation(9)).is_err());
+	assert_eq!(*dropped.borrow(), [9]);
+
+	let third = ring
+		.items_pinned_mut()
+		.nth(2)
+		.map(|item| &*item as *const _);
+	ring.push_overwrite(operation(3));
+	ring.push_overwrite(operation(4));
+	assert_eq!(*dropped.borrow(), [9, 0, 1]);
+
+	let ids = ring
+		.items_pinned_mut()
+		.map(|operation: Pin<&mut Operation>| operation.id)
+		.collect::<Vec<_>>();
+	assert_eq!(ids, [2, 3, 4]);
+	assert_eq!(
+		ring.items_pinned_mut()
+			.next()
+			.map(|item| &*item as *const _),
+		third
+	);
+
+	assert!(ring.pop_front_pinned());
+	assert_eq!(*dropped.borrow(), [9, 0, 1, 2]);
+	assert_eq!(ring.len(), 2);
+
+	drop(ring);
+	assert_eq!(*dropped.borrow(), [9, 0, 1, 2, 3, 4]);
+}
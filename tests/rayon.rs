@@ -0,0 +1,37 @@
+#![cfg(all(feature = "rayon", feature = "alloc"))]
+
+use napje::{role, ItemsPin};
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+use std::{marker::PhantomPinned, pin::Pin};
+
+struct Entity {
+	steps: usize,
+	_pinned: PhantomPinned,
+}
+
+impl Entity {
+	fn step(self: Pin<&mut Self>) {
+		unsafe { self.get_unchecked_mut() }.steps += 1
+	}
+}
+
+#[test]
+fn step_in_parallel() {
+	let mut entities = ItemsPin::<role::Items, Vec<_>>::pin(
+		(0..1000)
+			.map(|_| Entity {
+				steps: 0,
+				_pinned: PhantomPinned,
+			})
+			.collect::<Vec<_>>(),
+	);
+
+	assert_eq!(entities.par_items_pinned_mut().len(), 1000);
+	entities.par_items_pinned_mut().for_each(Entity::step);
+	entities
+		.as_mut_slice()
+		.par_items_pinned_mut()
+		.for_each(Entity::step);
+
+	assert!(entities.par_items_pinned().all(|entity| entity.steps == 2));
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "alloc")]
+
+use napje::{collections::ReservedPinnedVec, role, ItemsPin, ItemsPinned, ItemsPinnedMut};
+use std::{cell::RefCell, marker::PhantomPinned};
+
+struct Task<'a> {
+	id: usize,
+	log: &'a RefCell<Vec<usize>>,
+	_pinned: PhantomPinned,
+}
+
+impl Drop for Task<'_> {
+	fn drop(&mut self) {
+		self.log.borrow_mut().push(self.id)
+	}
+}
+
+fn push_without_moving(vec: ReservedPinnedVec<Task>, dropped: &RefCell<Vec<usize>>) {
+	let task = |id| Task {
+		id,
+		log: dropped,
+		_pinned: PhantomPinned,
+	};
+
+	let mut tasks = ItemsPin::<role::Items, ReservedPinnedVec<_>>::pin(vec);
+	tasks.push_pinned(task(0)).ok().unwrap();
+	let first = &*tasks.get_pinned(0).unwrap() as *const Task;
+
+	for id in 1..10_000 {
+		tasks.push_pinned(task(id)).ok().unwrap();
+	}
+	assert_eq!(
+		tasks.push_pinned(task(10_000)).map_err(|task| task.id),
+		Err(10_000)
+	);
+	dropped.borrow_mut().clear();
+
+	assert_eq!(&*tasks.get_pinned(0).unwrap() as *const _, first);
+	assert_eq!(tasks.get_pinned(9_999).unwrap().id, 9_999);
+	assert!(tasks.get_pinned(10_000).is_none());
+	assert_eq!(tasks.items_pinned_mut().count(), 10_000);
+	assert!(tasks
+		.items_pinned()
+		.enumerate()
+		.all(|(index, task)| task.id == index));
+
+	assert!(tasks.pop_pinned());
+	tasks.truncate_pinned(9_990);
+	assert_eq!(
+		*dropped.borrow(),
+		[9_999, 9_990, 9_991, 9_992, 9_993, 9_994, 9_995, 9_996, 9_997, 9_998]
+	);
+
+	dropped.borrow_mut().clear();
+	tasks.push_pinned(task(20_000)).ok().unwrap();
+	assert_eq!(tasks.get_pinned(9_990).unwrap().id, 20_000);
+	assert_eq!(&*tasks.get_pinned(0).unwrap() as *const _, first);
+
+	drop(tasks);
+	assert_eq!(dropped.borrow().len(), 9_991);
+	assert!(dropped.borrow().windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn reserved() {
+	let dropped = RefCell::new(Vec::new());
+	let vec = ReservedPinnedVec::with_reservation(10_000);
+	assert_eq!(vec.is_reserved(), cfg!(target_os = "linux"));
+	push_without_moving(vec, &dropped);
+}
+
+#[test]
+fn segmented() {
+	let dropped = RefCell::new(Vec::new());
+	let vec = ReservedPinnedVec::with_segments(10_000);
+	assert!(!vec.is_reserved());
+	push_without_moving(vec, &dropped);
+}
+
+#[test]
+fn zero_sized_items_use_segments() {
+	let mut vec = ReservedPinnedVec::with_reservation(3);
+	assert!(!vec.is_reserved());
+	for _ in 0..3 {
+		vec.push(()).unwrap();
+	}
+	assert_eq!(vec.push(()), Err(()));
+	assert_eq!(vec.pop(), Some(()));
+	assert_eq!(vec.len(), 2);
+}
+
+#[test]
+fn oversized_reservation_falls_back() {
+	let vec = ReservedPinnedVec::<u64>::with_reservation(usize::MAX);
+	assert!(!vec.is_reserved());
+	assert_eq!(vec.max_len(), usize::MAX);
+}
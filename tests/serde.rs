@@ -0,0 +1,20 @@
+#![cfg(all(feature = "serde", feature = "alloc"))]
+
+use napje::{role, ItemsPin};
+
+#[test]
+fn round_trip() {
+	let pool = ItemsPin::<role::Items, Vec<_>>::pin(vec![1, 2, 3]);
+	let json = serde_json::to_string(&pool).unwrap();
+	assert_eq!(json, "[1,2,3]");
+
+	let mut restored = ItemsPin::<role::Items, Vec<u8>>::deserialize_pinned_with_reserve(
+		&mut serde_json::Deserializer::from_str(&json),
+		2,
+	)
+	.unwrap();
+	assert_eq!(restored.as_slice().len(), 3);
+	assert_eq!(restored.push_pinned(4), Ok(()));
+	assert_eq!(restored.push_pinned(5), Ok(()));
+	assert_eq!(&**restored, &[1, 2, 3, 4, 5]);
+}
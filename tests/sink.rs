@@ -0,0 +1,187 @@
+#![cfg(feature = "futures")]
+
+use futures_sink::Sink;
+use napje::{
+	role,
+	sink::{Broadcast, FailurePolicy},
+	ItemsPin,
+};
+use std::{
+	cell::RefCell,
+	marker::PhantomPinned,
+	pin::Pin,
+	sync::Arc,
+	task::{Context, Poll, Wake, Waker},
+};
+
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+	fn wake(self: Arc<Self>) {}
+}
+
+/// Records items, is pending on its first `poll_ready` and fails when sent `fail_on`.
+struct Recorder<'a> {
+	id: usize,
+	fail_on: Option<u8>,
+	ready: bool,
+	items: Vec<u8>,
+	dropped: &'a RefCell<Vec<usize>>,
+	_pinned: PhantomPinned,
+}
+
+impl Sink<u8> for Recorder<'_> {
+	type Error = u8;
+
+	fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), u8>> {
+		let this = unsafe { self.get_unchecked_mut() };
+		if this.ready {
+			Poll::Ready(Ok(()))
+		} else {
+			this.ready = true;
+			Poll::Pending
+		}
+	}
+
+	fn start_send(self: Pin<&mut Self>, item: u8) -> Result<(), u8> {
+		let this = unsafe { self.get_unchecked_mut() };
+		if this.fail_on == Some(item) {
+			Err(item)
+		} else {
+			this.items.push(item);
+			Ok(())
+		}
+	}
+
+	fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), u8>> {
+		Poll::Ready(Ok(()))
+	}
+
+	fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), u8>> {
+		Poll::Ready(Ok(()))
+	}
+}
+
+impl Drop for Recorder<'_> {
+	fn drop(&mut self) {
+		self.dropped.borrow_mut().push(self.id)
+	}
+}
+
+fn recorders<'a>(
+	fail_on: &[Option<u8>],
+	dropped: &'a RefCell<Vec<usize>>,
+) -> ItemsPin<role::Items, Vec<Recorder<'a>>> {
+	ItemsPin::<role::Items, Vec<_>>::pin(
+		fail_on
+			.iter()
+			.enumerate()
+			.map(|(id, &fail_on)| Recorder {
+				id,
+				fail_on,
+				ready: false,
+				items: Vec::new(),
+				dropped,
+				_pinned: PhantomPinned,
+			})
+			.collect(),
+	)
+}
+
+fn items(broadcast: &Broadcast<Recorder>) -> Vec<Vec<u8>> {
+	broadcast
+		.sinks()
+		.iter()
+		.map(|recorder| recorder.items.clone())
+		.collect()
+}
+
+#[test]
+fn fan_out() {
+	let waker = Waker::from(Arc::new(NoopWaker));
+	let mut cx = Context::from_waker(&waker);
+	let dropped = RefCell::new(Vec::new());
+
+	let mut broadcast = Broadcast::new(recorders(&[None, None], &dropped), FailurePolicy::Report);
+	let mut broadcast = Pin::new(&mut broadcast);
+
+	assert!(broadcast.as_mut().poll_ready(&mut cx).is_pending());
+	assert_eq!(broadcast.as_mut().poll_ready(&mut cx), Poll::Ready(Ok(())));
+	broadcast.as_mut().start_send(1).unwrap();
+	broadcast.as_mut().start_send(2).unwrap();
+	assert_eq!(broadcast.as_mut().poll_flush(&mut cx), Poll::Ready(Ok(())));
+	assert_eq!(broadcast.as_mut().poll_close(&mut cx), Poll::Ready(Ok(())));
+	assert_eq!(items(&broadcast), [[1, 2], [1, 2]]);
+}
+
+#[test]
+fn report() {
+	let dropped = RefCell::new(Vec::new());
+	let mut broadcast = Broadcast::new(
+		recorders(&[None, Some(2), None], &dropped),
+		FailurePolicy::Report,
+	);
+	let mut broadcast = Pin::new(&mut broadcast);
+
+	broadcast.as_mut().start_send(1).unwrap();
+	assert_eq!(broadcast.as_mut().start_send(2), Err((1, 2)));
+	broadcast.as_mut().start_send(3).unwrap();
+
+	assert!(broadcast.is_failed(1));
+	// The sink after the failed one wasn't sent the item that failed.
+	assert_eq!(items(&broadcast), [vec![1, 2, 3], vec![1], vec![1, 3]]);
+	assert!(dropped.borrow().is_empty());
+}
+
+#[test]
+fn drop_failed() {
+	let dropped = RefCell::new(Vec::new());
+	let mut broadcast = Broadcast::new(
+		recorders(&[Some(3), None, Some(2), Some(2)], &dropped),
+		FailurePolicy::Drop,
+	);
+	Pin::new(&mut broadcast).start_send(1).unwrap();
+	Pin::new(&mut broadcast).start_send(2).unwrap();
+	// Both failed sinks were at the end, so they are dropped right away.
+	assert_eq!(*dropped.borrow(), [2, 3]);
+	assert_eq!(broadcast.sinks().len(), 2);
+
+	Pin::new(&mut broadcast).start_send(3).unwrap();
+	// Sink 0 stays in place unused, as sink 1 still works.
+	assert!(broadcast.is_failed(0));
+	assert_eq!(*dropped.borrow(), [2, 3]);
+	assert_eq!(items(&broadcast), [vec![1, 2], vec![1, 2, 3]]);
+
+	drop(broadcast);
+	assert_eq!(*dropped.borrow(), [2, 3, 0, 1]);
+}
+
+#[test]
+fn replaced_sinks_start_unfailed() {
+	let dropped = RefCell::new(Vec::new());
+	let recorder = |id, fail_on| Recorder {
+		id,
+		fail_on,
+		ready: false,
+		items: Vec::new(),
+		dropped: &dropped,
+		_pinned: PhantomPinned,
+	};
+
+	let mut sinks = Vec::with_capacity(2);
+	sinks.extend([recorder(0, None), recorder(1, Some(1))]);
+	let mut broadcast = Broadcast::new(
+		ItemsPin::<role::Items, Vec<_>>::pin(sinks),
+		FailurePolicy::Report,
+	);
+	assert_eq!(Pin::new(&mut broadcast).start_send(1), Err((1, 1)));
+	assert!(broadcast.is_failed(1));
+
+	broadcast.truncate_sinks(1);
+	assert_eq!(*dropped.borrow(), [1]);
+	assert!(broadcast.push_sink(recorder(2, None)).is_ok());
+	assert!(!broadcast.is_failed(1));
+
+	Pin::new(&mut broadcast).start_send(2).unwrap();
+	assert_eq!(items(&broadcast), [vec![1, 2], vec![2]]);
+}